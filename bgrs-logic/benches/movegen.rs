@@ -0,0 +1,242 @@
+// compares move generation against the original recursive implementation,
+// checking that both give the same plays. run with `cargo bench`.
+
+extern crate bgrs_logic;
+
+use bgrs_logic::{
+    BoardState, DiceRoll, DiceSource, DieRoll, Location, Move, PlayBuf,
+    PlayerColor, SeededDice,
+};
+use std::collections::VecDeque;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+// the original single die generator, copied here so that the comparison
+// doesn't depend on the code being measured
+fn reference_moves_for_single_die(
+    board: &BoardState,
+    die_roll: DieRoll,
+) -> Vec<Move> {
+    let player = board.cur_player;
+
+    // must enter checkers on bar if possible. otherwise, list the points
+    // the player is using, farthest first.
+    let sources: Vec<Location> = if board.checkers_at(player, Location::Bar) > 0
+    {
+        vec![Location::Bar]
+    } else {
+        (1..=24)
+            .rev()
+            .map(Location::Point)
+            .filter(|&loc| board.checkers_at(player, loc) > 0)
+            .collect()
+    };
+
+    let farthest = match sources.first() {
+        Some(loc) => loc.pip(),
+        None => return vec![],
+    };
+
+    let bearing_off = farthest <= 6;
+
+    sources
+        .into_iter()
+        .filter_map(|from| {
+            let from_pip = from.pip();
+
+            if die_roll < from_pip {
+                // the same point, numbered from the opponent's side, must
+                // have fewer than 2 of their checkers
+                let to_pip = from_pip - die_roll;
+                let blockers = board.checkers_at(
+                    player.inverse(),
+                    Location::Point(25 - to_pip),
+                );
+                if blockers < 2 {
+                    Some(Move(from, Location::Point(to_pip)))
+                } else {
+                    None
+                }
+            } else if bearing_off
+                && (die_roll == from_pip || from_pip == farthest)
+            {
+                // bearing off must be either exact, or else this is the
+                // farthest checker
+                Some(Move(from, Location::Off))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+// the original generator, which clones the board and builds a VecDeque for
+// every move it tries
+fn reference_backtrack(
+    board: &BoardState,
+    dice_slice: &[DieRoll],
+) -> Vec<VecDeque<Move>> {
+    let first_moves = reference_moves_for_single_die(board, dice_slice[0]);
+
+    let rest = &dice_slice[1..];
+    if rest.is_empty() {
+        return first_moves
+            .iter()
+            .map(|&move_| {
+                let mut v = VecDeque::new();
+                v.push_back(move_);
+                v
+            })
+            .collect();
+    }
+
+    first_moves
+        .iter()
+        .flat_map(|&move_| {
+            let mut next_move_seqs =
+                reference_backtrack(&board.with_move(move_), rest);
+
+            for next_moves in next_move_seqs.iter_mut() {
+                next_moves.push_front(move_);
+            }
+
+            if next_move_seqs.is_empty() {
+                let mut seq = VecDeque::new();
+                seq.push_back(move_);
+                next_move_seqs.push(seq);
+            }
+
+            next_move_seqs
+        })
+        .collect()
+}
+
+fn reference_move_seqs(board: &BoardState, dice: DiceRoll) -> Vec<Vec<Move>> {
+    let perms = if dice.0 == dice.1 {
+        vec![vec![dice.0; 4]]
+    } else {
+        vec![vec![dice.0, dice.1], vec![dice.1, dice.0]]
+    };
+
+    let move_seqs: Vec<(DieRoll, Vec<Move>)> = perms
+        .into_iter()
+        .flat_map(|perm| {
+            let first_die = perm[0];
+            reference_backtrack(board, &perm)
+                .into_iter()
+                .map(move |s| (first_die, s.into_iter().collect()))
+        })
+        .collect();
+
+    let max_seq_len = move_seqs.iter().map(|(_, s)| s.len()).max().unwrap_or(0);
+
+    let mut first_dice: Vec<DieRoll> =
+        move_seqs.iter().map(|&(die, _)| die).collect();
+    first_dice.dedup();
+
+    if dice.0 != dice.1 && max_seq_len == 1 && first_dice.len() >= 2 {
+        let larger_die = dice.0.max(dice.1);
+        return move_seqs
+            .into_iter()
+            .filter(|&(die, _)| die == larger_die)
+            .map(|(_, s)| s)
+            .collect();
+    }
+
+    move_seqs
+        .into_iter()
+        .map(|(_, s)| s)
+        .filter(|s| s.len() >= max_seq_len)
+        .collect()
+}
+
+// positions from a few games where each player makes an arbitrary legal play
+fn sample_positions() -> Vec<(BoardState, DiceRoll)> {
+    let mut dice = SeededDice::new(1);
+    let mut positions = vec![];
+    let mut plays = vec![];
+
+    for _ in 0..20 {
+        let mut board = BoardState::new_starting_state(PlayerColor::Black);
+
+        while board.get_winner().is_none() {
+            let roll = dice.roll_dice();
+            positions.push((board.clone(), roll));
+
+            board.generate_plays(roll, &mut plays);
+            if !plays.is_empty() {
+                let index = dice.roll_die() * 7 % plays.len();
+                board.apply_move_seq(plays[index].iter());
+            }
+
+            board.end_turn();
+        }
+    }
+
+    positions
+}
+
+fn time<F>(positions: &[(BoardState, DiceRoll)], mut f: F) -> Duration
+where
+    F: FnMut(&BoardState, DiceRoll),
+{
+    const ROUNDS: usize = 5;
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for (board, roll) in positions {
+            f(board, *roll);
+        }
+    }
+
+    start.elapsed() / (ROUNDS * positions.len()) as u32
+}
+
+fn bench(name: &str, positions: &[(BoardState, DiceRoll)]) {
+    let reference = time(positions, |board, roll| {
+        black_box(reference_move_seqs(board, roll));
+    });
+
+    let move_seqs = time(positions, |board, roll| {
+        black_box(board.get_move_seqs(roll));
+    });
+
+    let mut plays: Vec<PlayBuf> = vec![];
+    let buffered = time(positions, |board, roll| {
+        board.generate_plays(roll, &mut plays);
+        black_box(&plays);
+    });
+
+    println!(
+        "{} ({} positions): reference {:?}, get_move_seqs {:?} ({:.1}x), \
+         generate_plays {:?} ({:.1}x)",
+        name,
+        positions.len(),
+        reference,
+        move_seqs,
+        reference.as_secs_f64() / move_seqs.as_secs_f64(),
+        buffered,
+        reference.as_secs_f64() / buffered.as_secs_f64(),
+    );
+}
+
+fn main() {
+    let positions = sample_positions();
+
+    for (board, roll) in &positions {
+        assert_eq!(
+            board.get_move_seqs(*roll),
+            reference_move_seqs(board, *roll),
+            "different plays for {:?} in {:?}",
+            roll,
+            board,
+        );
+    }
+
+    let (doubles, non_doubles): (Vec<_>, Vec<_>) = positions
+        .into_iter()
+        .partition(|(_, roll)| roll.0 == roll.1);
+
+    bench("doubles", &doubles);
+    bench("non-doubles", &non_doubles);
+}
@@ -0,0 +1,141 @@
+use super::{
+    BoardState, Location, PlayerColor, PointState, CHECKERS_PER_PLAYER,
+};
+
+// number of locations stored per player: points 1-24 and the bar
+const LOCATIONS: usize = 25;
+
+// a position packed into a few machine words, for hashing and comparing lots
+// of positions quickly. each player's checkers are stored as 4 bits per
+// location, numbered from that player's point of view: bits 4 * (n - 1) hold
+// the count for point n, and the bar is stored as point 25. borne off checkers
+// aren't stored, as they're just whatever's left out of 15.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CompactBoard {
+    black: u128,
+    white: u128,
+    pub cur_player: PlayerColor,
+}
+
+impl CompactBoard {
+    pub fn from_board(board: &BoardState) -> Self {
+        Self {
+            black: Self::pack(board, PlayerColor::Black),
+            white: Self::pack(board, PlayerColor::White),
+            cur_player: board.cur_player,
+        }
+    }
+
+    // from counts in the same layout as counts(). the caller must make sure
+    // they make up a valid position.
+    pub(crate) fn from_counts(
+        black: &[u8; LOCATIONS],
+        white: &[u8; LOCATIONS],
+        cur_player: PlayerColor,
+    ) -> Self {
+        let pack_counts = |counts: &[u8; LOCATIONS]| {
+            counts
+                .iter()
+                .enumerate()
+                .fold(0, |bits, (i, &count)| bits | (count as u128) << (4 * i))
+        };
+
+        Self {
+            black: pack_counts(black),
+            white: pack_counts(white),
+            cur_player,
+        }
+    }
+
+    fn pack(board: &BoardState, player: PlayerColor) -> u128 {
+        (1..=LOCATIONS).fold(0, |bits, pip| {
+            let location = Location::from_pip(pip).unwrap();
+            let count = board.checkers_at(player, location) as u128;
+            bits | (count << (4 * (pip - 1)))
+        })
+    }
+
+    fn bits(&self, player: PlayerColor) -> u128 {
+        match player {
+            PlayerColor::Black => self.black,
+            PlayerColor::White => self.white,
+        }
+    }
+
+    // number of player's checkers at a location
+    pub fn checkers_at(
+        &self,
+        player: PlayerColor,
+        location: Location,
+    ) -> usize {
+        match location {
+            // an invalid board can have more than 15 checkers, which are
+            // then more than a u8 can add up
+            Location::Off => CHECKERS_PER_PLAYER.saturating_sub(
+                self.counts(player).iter().map(|&c| c as usize).sum(),
+            ),
+            _ => {
+                ((self.bits(player) >> (4 * (location.pip() - 1))) & 0xf)
+                    as usize
+            }
+        }
+    }
+
+    // player's checker counts for points 1-24 and then the bar, from the
+    // player's point of view. handy as input for neural nets.
+    pub fn counts(&self, player: PlayerColor) -> [u8; LOCATIONS] {
+        let bits = self.bits(player);
+        let mut counts = [0; LOCATIONS];
+        for (i, count) in counts.iter_mut().enumerate() {
+            *count = ((bits >> (4 * i)) & 0xf) as u8;
+        }
+
+        counts
+    }
+
+    pub fn to_board(&self) -> BoardState {
+        let mut points = [PointState::new(0, PlayerColor::Black); 26];
+        // bar points always keep their owner's color
+        for &player in [PlayerColor::Black, PlayerColor::White].iter() {
+            let bar_index = Location::Bar.to_index(player).unwrap();
+            points[bar_index].checker_color = player;
+        }
+
+        for &player in [PlayerColor::Black, PlayerColor::White].iter() {
+            for (i, &count) in self.counts(player).iter().enumerate() {
+                if count > 0 {
+                    let location = Location::from_pip(i + 1).unwrap();
+                    let index = location.to_index(player).unwrap();
+                    points[index] = PointState::new(count as usize, player);
+                }
+            }
+        }
+
+        BoardState {
+            points,
+            cur_player: self.cur_player,
+            black_borne_off: self
+                .checkers_at(PlayerColor::Black, Location::Off),
+            white_borne_off: self
+                .checkers_at(PlayerColor::White, Location::Off),
+        }
+    }
+}
+
+impl From<&BoardState> for CompactBoard {
+    fn from(board: &BoardState) -> Self {
+        Self::from_board(board)
+    }
+}
+
+impl From<CompactBoard> for BoardState {
+    fn from(compact: CompactBoard) -> Self {
+        compact.to_board()
+    }
+}
+
+impl BoardState {
+    pub fn to_compact(&self) -> CompactBoard {
+        CompactBoard::from_board(self)
+    }
+}
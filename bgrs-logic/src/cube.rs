@@ -0,0 +1,151 @@
+use super::{GameResult, PlayerColor, WinKind};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CubeError {
+    // doubling isn't allowed at all, e.g. during the crawford game
+    CubeDisabled,
+    // doubles must be offered before rolling the dice
+    AlreadyRolled,
+    // the other player owns the cube
+    NotCubeOwner,
+    DoubleAlreadyOffered,
+    NoDoubleOffered,
+    // the player who offered the double can't also answer it
+    OwnDouble,
+    BeaverNotAllowed,
+}
+
+impl fmt::Display for CubeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                CubeError::CubeDisabled => "doubling isn't allowed",
+                CubeError::AlreadyRolled => "can only double before rolling",
+                CubeError::NotCubeOwner => "cube is owned by the other player",
+                CubeError::DoubleAlreadyOffered => {
+                    "a double has already been offered"
+                }
+                CubeError::NoDoubleOffered => "no double has been offered",
+                CubeError::OwnDouble => "can't answer your own double",
+                CubeError::BeaverNotAllowed => "beavers aren't allowed",
+            }
+        )
+    }
+}
+
+impl Error for CubeError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CubeState {
+    pub value: usize,
+    // None while the cube is centered
+    pub owner: Option<PlayerColor>,
+    // player whose double is waiting for an answer
+    pub offered_by: Option<PlayerColor>,
+    pub enabled: bool,
+    pub beavers_allowed: bool,
+}
+
+impl Default for CubeState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CubeState {
+    pub fn new() -> Self {
+        Self {
+            value: 1,
+            owner: None,
+            offered_by: None,
+            enabled: true,
+            beavers_allowed: true,
+        }
+    }
+
+    pub fn is_centered(&self) -> bool {
+        self.owner.is_none()
+    }
+
+    // check whether player may offer a double, given whether they've already
+    // rolled the dice this turn
+    pub fn check_double(
+        &self,
+        player: PlayerColor,
+        has_rolled: bool,
+    ) -> Result<(), CubeError> {
+        if !self.enabled {
+            Err(CubeError::CubeDisabled)
+        } else if self.offered_by.is_some() {
+            Err(CubeError::DoubleAlreadyOffered)
+        } else if has_rolled {
+            Err(CubeError::AlreadyRolled)
+        } else if self.owner.is_some_and(|owner| owner != player) {
+            Err(CubeError::NotCubeOwner)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn double(
+        &mut self,
+        player: PlayerColor,
+        has_rolled: bool,
+    ) -> Result<(), CubeError> {
+        self.check_double(player, has_rolled)?;
+        self.offered_by = Some(player);
+        Ok(())
+    }
+
+    // check that player may answer the double that's been offered
+    fn check_response(&self, player: PlayerColor) -> Result<(), CubeError> {
+        match self.offered_by {
+            None => Err(CubeError::NoDoubleOffered),
+            Some(doubler) if doubler == player => Err(CubeError::OwnDouble),
+            Some(_) => Ok(()),
+        }
+    }
+
+    pub fn take(&mut self, player: PlayerColor) -> Result<(), CubeError> {
+        self.check_response(player)?;
+        self.offered_by = None;
+        self.value *= 2;
+        self.owner = Some(player);
+        Ok(())
+    }
+
+    // returns the result of the game, which the doubler wins at the cube's
+    // current value
+    pub fn drop_double(
+        &mut self,
+        player: PlayerColor,
+    ) -> Result<GameResult, CubeError> {
+        self.check_response(player)?;
+        self.offered_by = None;
+        Ok(GameResult::new(player.inverse(), WinKind::Single))
+    }
+
+    // take the double, and immediately redouble while keeping the cube
+    pub fn beaver(&mut self, player: PlayerColor) -> Result<(), CubeError> {
+        self.check_response(player)?;
+        if !self.beavers_allowed {
+            return Err(CubeError::BeaverNotAllowed);
+        }
+
+        self.offered_by = None;
+        self.value *= 4;
+        self.owner = Some(player);
+        Ok(())
+    }
+
+    // number of points that the result is worth with this cube
+    pub fn points(&self, result: &GameResult) -> usize {
+        result.points(self.value)
+    }
+}
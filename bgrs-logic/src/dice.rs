@@ -0,0 +1,129 @@
+use super::{DiceRoll, DieRoll};
+use std::collections::VecDeque;
+
+pub trait DiceSource {
+    fn roll_die(&mut self) -> DieRoll;
+
+    fn roll_dice(&mut self) -> DiceRoll {
+        (self.roll_die(), self.roll_die())
+    }
+
+    // seed that reproduces this source's rolls from the start, if any
+    fn seed(&self) -> Option<u64> {
+        None
+    }
+}
+
+// dice from a small PRNG (xorshift64*). it's implemented here rather than
+// taken from a crate so that a recorded seed always replays the same rolls.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeededDice {
+    seed: u64,
+    state: u64,
+}
+
+impl SeededDice {
+    pub fn new(seed: u64) -> Self {
+        let mut ret = Self {
+            seed,
+            // mix the seed up a bit, so that seed 0 doesn't give state 0,
+            // which xorshift would never leave
+            state: seed ^ 0x9e37_79b9_7f4a_7c15,
+        };
+
+        if ret.state == 0 {
+            ret.state = 1;
+        }
+
+        // nearby seeds start out with similar states, so drop a few values
+        for _ in 0..8 {
+            ret.next_u64();
+        }
+
+        ret
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+impl DiceSource for SeededDice {
+    fn roll_die(&mut self) -> DieRoll {
+        loop {
+            // use the top 3 bits, which are the best ones, and reject 6 and 7
+            // so that all rolls are equally likely
+            let bits = (self.next_u64() >> 61) as DieRoll;
+            if bits < 6 {
+                return bits + 1;
+            }
+        }
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed)
+    }
+}
+
+// dice given in advance, e.g. for tests and replays. panics if it runs out.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScriptedDice {
+    dice: VecDeque<DieRoll>,
+}
+
+impl ScriptedDice {
+    pub fn new<I>(dice: I) -> Self
+    where
+        I: IntoIterator<Item = DieRoll>,
+    {
+        Self {
+            dice: dice.into_iter().collect(),
+        }
+    }
+
+    pub fn from_rolls(rolls: &[DiceRoll]) -> Self {
+        Self::new(rolls.iter().flat_map(|&(a, b)| vec![a, b]))
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.dice.len()
+    }
+}
+
+impl DiceSource for ScriptedDice {
+    fn roll_die(&mut self) -> DieRoll {
+        self.dice.pop_front().expect("scripted dice ran out")
+    }
+}
+
+// dice entered by hand, e.g. when playing with physical dice. the callback
+// should return a number from 1 to 6.
+pub struct ManualDice<F>
+where
+    F: FnMut() -> DieRoll,
+{
+    read_die: F,
+}
+
+impl<F> ManualDice<F>
+where
+    F: FnMut() -> DieRoll,
+{
+    pub fn new(read_die: F) -> Self {
+        Self { read_die }
+    }
+}
+
+impl<F> DiceSource for ManualDice<F>
+where
+    F: FnMut() -> DieRoll,
+{
+    fn roll_die(&mut self) -> DieRoll {
+        (self.read_die)()
+    }
+}
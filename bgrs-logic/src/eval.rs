@@ -0,0 +1,61 @@
+use super::{BoardState, GameResult, PlayerColor, WinKind};
+
+// chances of each outcome of the game, for one of the players. winning a
+// gammon includes winning a backgammon, and winning includes both.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Probabilities {
+    pub win: f64,
+    pub win_gammon: f64,
+    pub win_backgammon: f64,
+    pub lose_gammon: f64,
+    pub lose_backgammon: f64,
+}
+
+impl Probabilities {
+    // certain outcome of a game that's over, for player
+    pub fn from_result(result: &GameResult, player: PlayerColor) -> Self {
+        let gammon = result.kind != WinKind::Single;
+        let backgammon = result.kind == WinKind::Backgammon;
+        let to_f64 = |b: bool| if b { 1.0 } else { 0.0 };
+
+        let won = Self {
+            win: 1.0,
+            win_gammon: to_f64(gammon),
+            win_backgammon: to_f64(backgammon),
+            lose_gammon: 0.0,
+            lose_backgammon: 0.0,
+        };
+
+        if result.winner == player {
+            won
+        } else {
+            won.invert()
+        }
+    }
+
+    // the same chances, from the opponent's point of view
+    pub fn invert(&self) -> Self {
+        Self {
+            win: 1.0 - self.win,
+            win_gammon: self.lose_gammon,
+            win_backgammon: self.lose_backgammon,
+            lose_gammon: self.win_gammon,
+            lose_backgammon: self.win_backgammon,
+        }
+    }
+
+    // expected points won per point on the cube, ignoring the cube's future
+    // use, from -3 to 3
+    pub fn equity(&self) -> f64 {
+        2.0 * self.win - 1.0 + self.win_gammon + self.win_backgammon
+            - self.lose_gammon
+            - self.lose_backgammon
+    }
+}
+
+// something that can judge positions, e.g. for a bot to choose its plays
+pub trait Evaluator {
+    // chances for the player on roll, who hasn't rolled yet
+    fn evaluate(&self, board: &BoardState) -> Probabilities;
+}
@@ -0,0 +1,373 @@
+use super::{
+    BoardState, CubeError, CubeState, DiceRoll, DiceSource, DieRoll,
+    GameResult, Move, PlayError, PlayerColor, WinKind,
+};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Phase {
+    // each player rolls a single die to decide who starts
+    OpeningRoll,
+    // player on roll may double or roll the dice
+    AwaitingRoll,
+    AwaitingMove(DiceRoll),
+    // a double has been offered, and the other player has to answer it
+    CubeDecision,
+    GameOver(GameResult),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    // die rolled by black, die rolled by white
+    OpeningRoll(DieRoll, DieRoll),
+    Roll(DiceRoll),
+    // a whole play for the current dice. empty if there are no legal moves.
+    Move(Vec<Move>),
+    Double,
+    Take,
+    Drop,
+    Beaver,
+    // give up the game, conceding a single game, a gammon or a backgammon.
+    // either player may resign at any time, and the game ends right away,
+    // as if the opponent accepted.
+    Resign(WinKind),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameError {
+    // action doesn't fit the current phase of the game
+    WrongPhase,
+    NotYourTurn,
+    InvalidDie,
+    IllegalPlay(PlayError),
+    Cube(CubeError),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::WrongPhase => write!(f, "action isn't allowed now"),
+            GameError::NotYourTurn => write!(f, "not your turn"),
+            GameError::InvalidDie => write!(f, "dice must be from 1 to 6"),
+            GameError::IllegalPlay(err) => write!(f, "illegal play: {}", err),
+            GameError::Cube(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for GameError {}
+
+impl From<PlayError> for GameError {
+    fn from(err: PlayError) -> Self {
+        GameError::IllegalPlay(err)
+    }
+}
+
+impl From<CubeError> for GameError {
+    fn from(err: CubeError) -> Self {
+        GameError::Cube(err)
+    }
+}
+
+// a single game, which checks that the players take turns and only make legal
+// actions
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Game {
+    board: BoardState,
+    cube: CubeState,
+    phase: Phase,
+    // number of times the cube may still be turned automatically when the
+    // opening roll is tied
+    auto_doubles_left: usize,
+    history: Vec<(PlayerColor, Action)>,
+    // seed of the dice used to play this game, so that it can be replayed
+    dice_seed: Option<u64>,
+    // where the game started, if not from the starting position
+    setup: Option<Setup>,
+}
+
+// a position to start a game from, e.g. one read from a match id or set up by
+// hand
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Setup {
+    pub board: BoardState,
+    pub cube: CubeState,
+    pub phase: Phase,
+}
+
+// player who wins the opening roll, or None if it's a tie
+pub fn opening_roll_winner(
+    black_die: DieRoll,
+    white_die: DieRoll,
+) -> Option<PlayerColor> {
+    if black_die > white_die {
+        Some(PlayerColor::Black)
+    } else if white_die > black_die {
+        Some(PlayerColor::White)
+    } else {
+        None
+    }
+}
+
+impl Game {
+    // start a game in which starting_player is already known and rolls first
+    pub fn new(starting_player: PlayerColor, cube: CubeState) -> Self {
+        Self {
+            board: BoardState::new_starting_state(starting_player),
+            cube,
+            phase: Phase::AwaitingRoll,
+            auto_doubles_left: 0,
+            history: vec![],
+            dice_seed: None,
+            setup: None,
+        }
+    }
+
+    // start a game with an opening roll. in money play, tied opening rolls
+    // may double the cube automatically, up to max_auto_doubles times.
+    pub fn new_with_opening_roll(
+        cube: CubeState,
+        max_auto_doubles: usize,
+    ) -> Self {
+        Self {
+            phase: Phase::OpeningRoll,
+            auto_doubles_left: max_auto_doubles,
+            ..Self::new(PlayerColor::Black, cube)
+        }
+    }
+
+    // a game that starts from a position other than the starting position.
+    // the history only has the actions taken after the setup.
+    pub fn from_setup(setup: Setup) -> Self {
+        Self {
+            board: setup.board.clone(),
+            cube: setup.cube,
+            phase: setup.phase,
+            auto_doubles_left: 0,
+            history: vec![],
+            dice_seed: None,
+            setup: Some(setup),
+        }
+    }
+
+    pub fn board(&self) -> &BoardState {
+        &self.board
+    }
+
+    pub fn cube(&self) -> &CubeState {
+        &self.cube
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    // actions applied so far, and the player who made each of them
+    pub fn history(&self) -> &[(PlayerColor, Action)] {
+        &self.history
+    }
+
+    // the position the game started from, or None if it's the starting
+    // position
+    pub fn setup(&self) -> Option<&Setup> {
+        self.setup.as_ref()
+    }
+
+    pub fn dice_seed(&self) -> Option<u64> {
+        self.dice_seed
+    }
+
+    pub fn set_dice_seed(&mut self, seed: Option<u64>) {
+        self.dice_seed = seed;
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn auto_doubles_left(&self) -> usize {
+        self.auto_doubles_left
+    }
+
+    pub fn result(&self) -> Option<GameResult> {
+        match self.phase {
+            Phase::GameOver(result) => Some(result),
+            _ => None,
+        }
+    }
+
+    // points won by the winner, once the game is over
+    pub fn points(&self) -> Option<usize> {
+        self.result().map(|result| self.cube.points(&result))
+    }
+
+    // player who has to make the next action. both players roll the opening
+    // roll together, and nobody acts after the game is over.
+    pub fn player_to_act(&self) -> Option<PlayerColor> {
+        match self.phase {
+            Phase::OpeningRoll | Phase::GameOver(_) => None,
+            Phase::AwaitingRoll | Phase::AwaitingMove(_) => {
+                Some(self.board.cur_player)
+            }
+            Phase::CubeDecision => Some(self.board.cur_player.inverse()),
+        }
+    }
+
+    // legal plays for the current dice, if the player on roll has to move
+    pub fn legal_plays(&self) -> Vec<Vec<Move>> {
+        match self.phase {
+            Phase::AwaitingMove(dice) => self.board.get_move_seqs(dice),
+            _ => vec![],
+        }
+    }
+
+    pub fn apply(
+        &mut self,
+        player: PlayerColor,
+        action: Action,
+    ) -> Result<(), GameError> {
+        if let Action::Resign(kind) = action {
+            if self.result().is_some() {
+                return Err(GameError::WrongPhase);
+            }
+
+            self.phase =
+                Phase::GameOver(GameResult::new(player.inverse(), kind));
+            self.history.push((player, action));
+            return Ok(());
+        }
+
+        if let Some(expected) = self.player_to_act() {
+            if player != expected {
+                return Err(GameError::NotYourTurn);
+            }
+        }
+
+        let new_phase = match (self.phase, &action) {
+            (
+                Phase::OpeningRoll,
+                &Action::OpeningRoll(black_die, white_die),
+            ) => {
+                Self::check_die(black_die)?;
+                Self::check_die(white_die)?;
+                self.opening_roll(black_die, white_die)
+            }
+
+            (Phase::AwaitingRoll, &Action::Roll(dice)) => {
+                Self::check_die(dice.0)?;
+                Self::check_die(dice.1)?;
+                Phase::AwaitingMove(dice)
+            }
+
+            (Phase::AwaitingMove(dice), Action::Move(move_seq)) => {
+                self.play(dice, move_seq)?
+            }
+
+            (Phase::AwaitingRoll, Action::Double) => {
+                self.cube.double(player, false)?;
+                Phase::CubeDecision
+            }
+
+            (Phase::AwaitingMove(_), Action::Double) => {
+                return Err(CubeError::AlreadyRolled.into());
+            }
+
+            (Phase::CubeDecision, Action::Take) => {
+                self.cube.take(player)?;
+                Phase::AwaitingRoll
+            }
+
+            (Phase::CubeDecision, Action::Drop) => {
+                Phase::GameOver(self.cube.drop_double(player)?)
+            }
+
+            (Phase::CubeDecision, Action::Beaver) => {
+                self.cube.beaver(player)?;
+                Phase::AwaitingRoll
+            }
+
+            _ => return Err(GameError::WrongPhase),
+        };
+
+        self.phase = new_phase;
+        self.history.push((player, action));
+        Ok(())
+    }
+
+    // roll the opening roll or player's dice using a dice source, and return
+    // the action that was applied
+    pub fn roll(
+        &mut self,
+        player: PlayerColor,
+        dice: &mut dyn DiceSource,
+    ) -> Result<Action, GameError> {
+        let action = match self.phase {
+            Phase::OpeningRoll => {
+                let (black_die, white_die) = dice.roll_dice();
+                Action::OpeningRoll(black_die, white_die)
+            }
+            Phase::AwaitingRoll => Action::Roll(dice.roll_dice()),
+            _ => return Err(GameError::WrongPhase),
+        };
+
+        self.apply(player, action.clone())?;
+
+        if self.dice_seed.is_none() {
+            self.dice_seed = dice.seed();
+        }
+
+        Ok(action)
+    }
+
+    fn check_die(die: DieRoll) -> Result<(), GameError> {
+        if (1..=6).contains(&die) {
+            Ok(())
+        } else {
+            Err(GameError::InvalidDie)
+        }
+    }
+
+    fn opening_roll(
+        &mut self,
+        black_die: DieRoll,
+        white_die: DieRoll,
+    ) -> Phase {
+        match opening_roll_winner(black_die, white_die) {
+            Some(winner) => {
+                // winner of the opening roll plays both dice
+                self.board.cur_player = winner;
+                Phase::AwaitingMove((black_die, white_die))
+            }
+
+            None => {
+                // tie, roll again. the cube stays in the middle.
+                if self.auto_doubles_left > 0 && self.cube.enabled {
+                    self.auto_doubles_left -= 1;
+                    self.cube.value *= 2;
+                }
+
+                Phase::OpeningRoll
+            }
+        }
+    }
+
+    fn play(
+        &mut self,
+        dice: DiceRoll,
+        move_seq: &[Move],
+    ) -> Result<Phase, GameError> {
+        self.board.is_legal_play(dice, move_seq)?;
+
+        self.board.apply_move_seq(move_seq.iter());
+
+        if let Some(result) = self.board.get_result() {
+            return Ok(Phase::GameOver(result));
+        }
+
+        self.board.end_turn();
+        Ok(Phase::AwaitingRoll)
+    }
+}
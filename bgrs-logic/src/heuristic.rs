@@ -0,0 +1,233 @@
+use super::{BoardState, Evaluator, Location, PlayerColor, Probabilities};
+
+// how much each feature of a position is worth. the features of the player
+// on roll minus the opponent's, times these weights, add up to a score that's
+// turned into a chance of winning.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeuristicWeights {
+    // per pip left to move, which counts against the player
+    pub pips: f64,
+    // per blot that the opponent can hit with a single die, or with a
+    // combination of dice
+    pub direct_blots: f64,
+    pub indirect_blots: f64,
+    // per point held by 2 or more checkers, and extra for each one in the
+    // home board
+    pub made_points: f64,
+    pub home_points: f64,
+    // per point in the longest run of made points
+    pub prime: f64,
+    // per point held in the opponent's home board
+    pub anchors: f64,
+    // per checker in the opponent's home board or on the bar
+    pub back_checkers: f64,
+    pub bar: f64,
+    pub borne_off: f64,
+    // for being the one to roll next
+    pub on_roll: f64,
+}
+
+impl Default for HeuristicWeights {
+    fn default() -> Self {
+        Self {
+            pips: 0.015,
+            direct_blots: -0.35,
+            indirect_blots: -0.15,
+            made_points: 0.1,
+            home_points: 0.15,
+            prime: 0.15,
+            anchors: 0.2,
+            back_checkers: -0.05,
+            bar: -0.3,
+            borne_off: 0.05,
+            on_roll: 0.1,
+        }
+    }
+}
+
+// a player's side of a position
+struct Features {
+    pips: usize,
+    direct_blots: usize,
+    indirect_blots: usize,
+    made_points: usize,
+    home_points: usize,
+    prime: usize,
+    anchors: usize,
+    back_checkers: usize,
+    bar: usize,
+    borne_off: usize,
+}
+
+impl Features {
+    fn new(board: &BoardState, player: PlayerColor) -> Self {
+        let opponent = player.inverse();
+        let count = |n: usize| board.checkers_at(player, Location::Point(n));
+
+        // distance from the opponent's farthest checker back to each point
+        // still ahead of it, to see which blots it can reach
+        let opponent_farthest = (1..=25)
+            .rev()
+            .find(|&pip| {
+                board.checkers_at(opponent, Location::from_pip(pip).unwrap())
+                    > 0
+            })
+            .unwrap_or(0);
+
+        let mut features = Features {
+            pips: board.pip_count(player),
+            direct_blots: 0,
+            indirect_blots: 0,
+            made_points: 0,
+            home_points: 0,
+            prime: 0,
+            anchors: 0,
+            back_checkers: board.checkers_at(player, Location::Bar),
+            bar: board.checkers_at(player, Location::Bar),
+            borne_off: board.borne_off(player),
+        };
+
+        let mut run = 0;
+        for n in 1..=24 {
+            let checkers = count(n);
+
+            if checkers >= 2 {
+                features.made_points += 1;
+                run += 1;
+                features.prime = features.prime.max(run);
+
+                if n <= 6 {
+                    features.home_points += 1;
+                }
+                if n >= 19 {
+                    features.anchors += 1;
+                }
+            } else {
+                run = 0;
+            }
+
+            if n >= 19 {
+                features.back_checkers += checkers;
+            }
+
+            if checkers == 1 {
+                // the same point, numbered from the opponent's side
+                let opponent_pip = 25 - n;
+                let shots = (opponent_pip + 1..=opponent_farthest)
+                    .filter(|&pip| {
+                        let location = Location::from_pip(pip).unwrap();
+                        board.checkers_at(opponent, location) > 0
+                    })
+                    .map(|pip| pip - opponent_pip)
+                    .collect::<Vec<usize>>();
+
+                if shots.iter().any(|&distance| distance <= 6) {
+                    features.direct_blots += 1;
+                } else if shots.iter().any(|&distance| distance <= 12) {
+                    features.indirect_blots += 1;
+                }
+            }
+        }
+
+        features
+    }
+
+    fn score(&self, weights: &HeuristicWeights) -> f64 {
+        // fewer pips are better, so the pips are counted against the player
+        -weights.pips * self.pips as f64
+            + weights.direct_blots * self.direct_blots as f64
+            + weights.indirect_blots * self.indirect_blots as f64
+            + weights.made_points * self.made_points as f64
+            + weights.home_points * self.home_points as f64
+            + weights.prime * self.prime as f64
+            + weights.anchors * self.anchors as f64
+            + weights.back_checkers * self.back_checkers as f64
+            + weights.bar * self.bar as f64
+            + weights.borne_off * self.borne_off as f64
+    }
+}
+
+fn logistic(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+// chance that a game won by player is a gammon, and chance that a gammon is
+// a backgammon
+fn gammon_chances(board: &BoardState, player: PlayerColor) -> (f64, f64) {
+    let loser = player.inverse();
+    if board.borne_off(loser) > 0 {
+        return (0.0, 0.0);
+    }
+
+    // pips the loser needs to bring their checkers home and bear one off,
+    // against the pips the winner needs to finish, which they have time to
+    // move about twice as fast as the loser moves theirs on average
+    let checkers =
+        |pip: usize| board.checkers_at(loser, Location::from_pip(pip).unwrap());
+    let lowest = (1..=6).find(|&pip| checkers(pip) > 0).unwrap_or(6);
+    let to_save: usize = (7..=25).map(|pip| checkers(pip) * (pip - 6)).sum();
+    let race = (to_save + lowest) as f64 - board.pip_count(player) as f64 / 2.0;
+
+    // checkers stuck in the winner's home board, counting the bar
+    let trapped: usize = (19..=25).map(checkers).sum();
+
+    let gammon = 0.6 * logistic(race / 5.0);
+    let backgammon = (0.1 * trapped as f64).min(0.5);
+    (gammon, backgammon)
+}
+
+// a baseline evaluator that adds up weighted features of the position, like
+// a human player's rules of thumb. races are judged by the pip counts alone.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeuristicEvaluator {
+    pub weights: HeuristicWeights,
+}
+
+impl HeuristicEvaluator {
+    pub fn new(weights: HeuristicWeights) -> Self {
+        Self { weights }
+    }
+
+    // score for the player on roll, which is 0 for an even game
+    fn score(&self, board: &BoardState) -> f64 {
+        let player = board.cur_player;
+        let opponent = player.inverse();
+
+        if board.is_race() {
+            // being on roll is worth about 4 pips, and a lead counts for less
+            // the longer the race is
+            let lead = board.pip_difference(player) as f64 + 4.0;
+            let length =
+                (board.pip_count(player) + board.pip_count(opponent)) as f64;
+            return lead / (2.0 + 0.04 * length);
+        }
+
+        Features::new(board, player).score(&self.weights)
+            - Features::new(board, opponent).score(&self.weights)
+            + self.weights.on_roll
+    }
+}
+
+impl Evaluator for HeuristicEvaluator {
+    fn evaluate(&self, board: &BoardState) -> Probabilities {
+        let player = board.cur_player;
+        if let Some(result) = board.get_result() {
+            return Probabilities::from_result(&result, player);
+        }
+
+        let win = logistic(self.score(board));
+        let (gammon, backgammon) = gammon_chances(board, player);
+        let (lose_gammon, lose_backgammon) =
+            gammon_chances(board, player.inverse());
+
+        Probabilities {
+            win,
+            win_gammon: win * gammon,
+            win_backgammon: win * gammon * backgammon,
+            lose_gammon: (1.0 - win) * lose_gammon,
+            lose_backgammon: (1.0 - win) * lose_gammon * lose_backgammon,
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use super::{BoardState, DiceRoll, DieRoll, Move, MoveError};
+use std::error::Error;
+use std::fmt;
+
+// reasons for rejecting a play. indices refer to the move in the play that
+// caused the problem.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlayError {
+    IllegalMove(usize, MoveError),
+    // move doesn't fit any of the dice that are left
+    DiceMismatch(usize),
+    TooManyMoves,
+    // player must play as many dice as possible. doubles says whether the
+    // dice were doubles, which can be played as up to 4 moves.
+    TooFewMoves { required: usize, doubles: bool },
+    // if only one die can be played, it must be the larger one when possible
+    MustUseLargerDie,
+}
+
+impl fmt::Display for PlayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayError::IllegalMove(index, err) => {
+                write!(f, "move {}: {}", index + 1, err)
+            }
+            PlayError::DiceMismatch(index) => {
+                write!(f, "move {} doesn't match the dice", index + 1)
+            }
+            PlayError::TooManyMoves => write!(f, "too many moves"),
+            PlayError::TooFewMoves {
+                required: 2,
+                doubles: false,
+            } => write!(f, "must use both dice"),
+            PlayError::TooFewMoves { required: 1, .. } => {
+                write!(f, "must play 1 move")
+            }
+            PlayError::TooFewMoves { required, .. } => {
+                write!(f, "must play {} moves", required)
+            }
+            PlayError::MustUseLargerDie => write!(f, "must use the larger die"),
+        }
+    }
+}
+
+impl Error for PlayError {}
+
+impl BoardState {
+    // check whether move_seq is a legal play for the current player with the
+    // given dice, and explain why not if it isn't
+    pub fn is_legal_play(
+        &self,
+        dice_roll: DiceRoll,
+        move_seq: &[Move],
+    ) -> Result<(), PlayError> {
+        let valid_move_seqs = self.get_move_seqs(dice_roll);
+        if valid_move_seqs.iter().any(|s| s.as_slice() == move_seq)
+            || (valid_move_seqs.is_empty() && move_seq.is_empty())
+        {
+            return Ok(());
+        }
+
+        let dice = if dice_roll.0 == dice_roll.1 {
+            vec![dice_roll.0; 4]
+        } else {
+            vec![dice_roll.0, dice_roll.1]
+        };
+
+        if move_seq.len() > dice.len() {
+            return Err(PlayError::TooManyMoves);
+        }
+
+        // check the moves themselves, regardless of dice
+        let mut board = self.clone();
+        for (i, &move_) in move_seq.iter().enumerate() {
+            board
+                .try_apply_move(move_)
+                .map_err(|err| PlayError::IllegalMove(i, err))?;
+        }
+
+        // then check that each move can be made with a different die
+        let matched = self.count_dice_matches(&dice, move_seq);
+        if matched < move_seq.len() {
+            return Err(PlayError::DiceMismatch(matched));
+        }
+
+        // all valid plays are the same length, as player must make the
+        // maximum number of moves available
+        let required = valid_move_seqs.first().map_or(0, |s| s.len());
+        if move_seq.len() < required {
+            return Err(PlayError::TooFewMoves {
+                required,
+                doubles: dice_roll.0 == dice_roll.1,
+            });
+        }
+
+        // the play fits the dice and is long enough, so the only way it isn't
+        // one of the valid plays is if it used the smaller die
+        Err(PlayError::MustUseLargerDie)
+    }
+
+    // returns the length of the longest prefix of move_seq that can be played
+    // using a different one of the dice for each move
+    fn count_dice_matches(&self, dice: &[DieRoll], move_seq: &[Move]) -> usize {
+        let move_ = match move_seq.first() {
+            Some(&move_) => move_,
+            None => return 0,
+        };
+
+        let mut best = 0;
+        for (i, &die) in dice.iter().enumerate() {
+            // skip dice we've already tried
+            if dice[..i].contains(&die)
+                || !self.get_moves_for_single_die(die).contains(&move_)
+            {
+                continue;
+            }
+
+            let mut rest = dice.to_vec();
+            rest.remove(i);
+
+            let matched = 1 + self
+                .with_move(move_)
+                .count_dice_matches(&rest, &move_seq[1..]);
+
+            if matched == move_seq.len() {
+                return matched;
+            }
+
+            best = best.max(matched);
+        }
+
+        best
+    }
+}
@@ -1,12 +1,50 @@
-extern crate dedup_iter;
-
-use dedup_iter::DedupAdapter;
-use std::cmp::max;
-use std::collections::VecDeque;
+use std::cmp::Ordering;
+use std::error::Error;
 use std::fmt;
-use std::iter::repeat;
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+use std::hash::{Hash, Hasher};
+
+mod compact;
+mod cube;
+mod dice;
+mod eval;
+mod game;
+mod heuristic;
+mod legality;
+mod mat;
+mod match_id;
+mod match_play;
+mod movegen;
+mod notation;
+mod pips;
+mod play;
+mod position_id;
+mod search;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod sgf;
+mod xgid;
+
+pub use compact::CompactBoard;
+pub use cube::{CubeError, CubeState};
+pub use dice::{DiceSource, ManualDice, ScriptedDice, SeededDice};
+pub use eval::{Evaluator, Probabilities};
+pub use game::{opening_roll_winner, Action, Game, GameError, Phase, Setup};
+pub use heuristic::{HeuristicEvaluator, HeuristicWeights};
+pub use legality::PlayError;
+pub use mat::{MatError, MatErrorKind, MatchRecord};
+pub use match_id::{MatchId, MatchIdError, MatchIdGameState};
+pub use match_play::Match;
+pub use movegen::{PlayBuf, MAX_PLAY_LEN};
+pub use notation::NotationError;
+pub use pips::{BearoffTable, RaceAdvice};
+pub use play::Play;
+pub use position_id::PositionIdError;
+pub use search::{MoveFilter, Search, SearchSettings};
+pub use sgf::{Sgf, SgfError, SgfErrorKind, SgfNode, SgfProperty, SgfTree};
+pub use xgid::{Xgid, XgidError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlayerColor {
     Black,
     White,
@@ -35,6 +73,74 @@ impl fmt::Display for PlayerColor {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WinKind {
+    Single,
+    Gammon,
+    Backgammon,
+}
+
+impl WinKind {
+    // how many times the cube value the winner gets
+    pub fn multiplier(self) -> usize {
+        match self {
+            WinKind::Single => 1,
+            WinKind::Gammon => 2,
+            WinKind::Backgammon => 3,
+        }
+    }
+
+    // the kind of win that gets multiplier times the cube value, e.g. to tell
+    // what a resignation conceded from the points it gave
+    pub fn from_multiplier(multiplier: usize) -> Option<Self> {
+        match multiplier {
+            1 => Some(WinKind::Single),
+            2 => Some(WinKind::Gammon),
+            3 => Some(WinKind::Backgammon),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameResult {
+    pub winner: PlayerColor,
+    pub kind: WinKind,
+}
+
+impl GameResult {
+    pub fn new(winner: PlayerColor, kind: WinKind) -> Self {
+        Self { winner, kind }
+    }
+
+    pub fn loser(&self) -> PlayerColor {
+        self.winner.inverse()
+    }
+
+    // number of points won, given the final value of the cube
+    pub fn points(&self, cube_value: usize) -> usize {
+        self.kind.multiplier() * cube_value
+    }
+}
+
+impl fmt::Display for GameResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} won{}",
+            self.winner,
+            match self.kind {
+                WinKind::Single => "",
+                WinKind::Gammon => " a gammon",
+                WinKind::Backgammon => " a backgammon",
+            }
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PointState {
     pub checker_count: usize,
     pub checker_color: PlayerColor,
@@ -57,22 +163,170 @@ impl PointState {
     }
 }
 
+// an empty point's color is just whoever used it last, so it doesn't count
+impl PartialEq for PointState {
+    fn eq(&self, other: &Self) -> bool {
+        self.checker_count == other.checker_count
+            && (self.is_empty() || self.checker_color == other.checker_color)
+    }
+}
+
+impl Eq for PointState {}
+
+impl Hash for PointState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.checker_count.hash(state);
+        if !self.is_empty() {
+            self.checker_color.hash(state);
+        }
+    }
+}
+
+// index into BoardState::points. 0 is black's bar, 25 is white's bar.
 pub type PointIndex = usize;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct Move(pub PointIndex, pub PointIndex);
+pub const CHECKERS_PER_PLAYER: usize = 15;
+
+pub type DieRoll = usize;
+
+pub type DiceRoll = (DieRoll, DieRoll);
+
+// somewhere a checker can be, numbered from the point of view of the player
+// who owns it: checkers enter from the bar onto point 24, move down towards
+// point 1, and are then borne off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Location {
+    Bar,
+    Point(usize),
+    Off,
+}
+
+impl Location {
+    // distance left until the checker is borne off, so the bar is 25 and off
+    // is 0
+    pub fn pip(self) -> usize {
+        match self {
+            Location::Bar => 25,
+            Location::Point(n) => n,
+            Location::Off => 0,
+        }
+    }
+
+    pub fn from_pip(pip: usize) -> Option<Self> {
+        match pip {
+            0 => Some(Location::Off),
+            1..=24 => Some(Location::Point(pip)),
+            25 => Some(Location::Bar),
+            _ => None,
+        }
+    }
+
+    // index into BoardState::points of this location for player's checkers.
+    // checkers that are off aren't stored in points, so there's no index for
+    // them.
+    pub fn to_index(self, player: PlayerColor) -> Option<PointIndex> {
+        match self {
+            Location::Off => None,
+            _ => Some(match player {
+                PlayerColor::Black => 25 - self.pip(),
+                PlayerColor::White => self.pip(),
+            }),
+        }
+    }
+
+    // inverse of to_index(). note that the other player's bar maps to Off.
+    pub fn from_index(player: PlayerColor, index: PointIndex) -> Self {
+        let pip = match player {
+            PlayerColor::Black => 25 - index,
+            PlayerColor::White => index,
+        };
+        Self::from_pip(pip).unwrap()
+    }
+}
+
+// locations are sorted in the order that a checker passes through them
+impl Ord for Location {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.pip().cmp(&self.pip())
+    }
+}
+
+impl PartialOrd for Location {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Location::Bar => write!(f, "bar"),
+            Location::Point(n) => write!(f, "{}", n),
+            Location::Off => write!(f, "off"),
+        }
+    }
+}
+
+// from and to locations are relative to the player making the move
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Move(pub Location, pub Location);
 
 impl Move {
-    // get die roll used to make this move
+    // get number of pips covered by this move. this is the die roll used to
+    // make the move, except when bearing off with a larger die.
     pub fn die_roll(&self) -> usize {
-        ((self.1 as isize) - (self.0 as isize)).abs() as usize
+        self.0.pip().abs_diff(self.1.pip())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MoveError {
+    // a point number outside of 1-24
+    InvalidLocation,
+    NoCheckerAtSource,
+    // destination has 2 or more of the other player's checkers
+    BlockedDestination,
+    // checkers can only move towards the player's home
+    WrongDirection,
+    // bearing off before all checkers are in the home board
+    IllegalBearOff,
+    MustEnterFromBar,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                MoveError::InvalidLocation => "no such point",
+                MoveError::NoCheckerAtSource => "no checker to move",
+                MoveError::BlockedDestination => "destination is blocked",
+                MoveError::WrongDirection => "moving in the wrong direction",
+                MoveError::IllegalBearOff => {
+                    "can't bear off before all checkers are home"
+                }
+                MoveError::MustEnterFromBar => {
+                    "must enter checkers from the bar first"
+                }
+            }
+        )
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+impl Error for MoveError {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct BoardState {
     pub points: [PointState; 26],
     pub cur_player: PlayerColor,
+    // checkers that have already been borne off, per player
+    pub black_borne_off: usize,
+    pub white_borne_off: usize,
 }
 
 impl BoardState {
@@ -98,6 +352,22 @@ impl BoardState {
         BoardState {
             points,
             cur_player: starting_player,
+            black_borne_off: 0,
+            white_borne_off: 0,
+        }
+    }
+
+    pub fn borne_off(&self, player: PlayerColor) -> usize {
+        match player {
+            PlayerColor::Black => self.black_borne_off,
+            PlayerColor::White => self.white_borne_off,
+        }
+    }
+
+    fn borne_off_mut(&mut self, player: PlayerColor) -> &mut usize {
+        match player {
+            PlayerColor::Black => &mut self.black_borne_off,
+            PlayerColor::White => &mut self.white_borne_off,
         }
     }
 
@@ -138,7 +408,7 @@ impl BoardState {
 
     pub fn print(&self) {
         for row in 0..6 {
-            self.print_points(row, (&self.points[13..19]).iter());
+            self.print_points(row, self.points[13..19].iter());
 
             print!(
                 " {} ",
@@ -149,7 +419,7 @@ impl BoardState {
                 }
             );
 
-            self.print_points(row, (&self.points[19..25]).iter());
+            self.print_points(row, self.points[19..25].iter());
             println!();
         }
 
@@ -171,6 +441,26 @@ impl BoardState {
             self.print_points(row, self.points[1..7].iter().rev());
             println!();
         }
+
+        println!();
+        println!(
+            "off: {} {}, {} {}",
+            Self::get_checker_string(PlayerColor::Black),
+            self.black_borne_off,
+            Self::get_checker_string(PlayerColor::White),
+            self.white_borne_off,
+        );
+    }
+
+    // number of player's checkers at a location
+    pub fn checkers_at(&self, player: PlayerColor, location: Location) -> usize {
+        match location.to_index(player) {
+            Some(index) if self.points[index].is_used_by(player) => {
+                self.points[index].checker_count
+            }
+            Some(_) => 0,
+            None => self.borne_off(player),
+        }
     }
 
     pub fn used_points(&self, player: PlayerColor) -> Vec<PointIndex> {
@@ -182,145 +472,101 @@ impl BoardState {
             .collect()
     }
 
-    // swap point index if player is white, so that 1 is start point and 24
-    // is end point
-    fn reverse_white_point(
-        player: PlayerColor,
-        point: PointIndex,
-    ) -> PointIndex {
-        match player {
-            PlayerColor::White => Self::get_opposite(point),
-            _ => point,
-        }
+    // ignores effects of other die
+    pub fn get_moves_for_single_die(&self, die_roll: DieRoll) -> Vec<Move> {
+        let (moves, count) = self.single_die_moves(die_roll);
+        moves[..count].to_vec()
     }
 
-    // like reverse_white_point() for a Vec
-    fn reverse_white_points(
-        player: PlayerColor,
-        index_vec: &mut Vec<PointIndex>,
-    ) {
-        if player == PlayerColor::White {
-            index_vec
-                .iter_mut()
-                .for_each(|i| *i = Self::get_opposite(*i));
-        }
+    // whether player may move a checker to a location on the board, i.e. it's
+    // empty, used by player, or a blot of the other player
+    fn is_open_for(&self, player: PlayerColor, location: Location) -> bool {
+        self.checkers_at(player.inverse(), Self::as_seen_by_opponent(location))
+            < 2
     }
 
-    // like reverse_white_points() for a Vec<Move>
-    fn reverse_white_moves(player: PlayerColor, move_vec: &mut Vec<Move>) {
-        if player == PlayerColor::White {
-            move_vec.iter_mut().for_each(|m| {
-                m.0 = Self::get_opposite(m.0);
-                m.1 = Self::get_opposite(m.1);
-            });
+    // the same point on the board, numbered from the other player's view
+    fn as_seen_by_opponent(location: Location) -> Location {
+        match location {
+            Location::Point(n) => Location::Point(25 - n),
+            _ => location,
         }
     }
 
-    // ignores effects of other die
-    pub fn get_moves_for_single_die(&self, die_roll: usize) -> Vec<Move> {
-        // must enter checkers on bar if possible
-        let cur_player_bar = Self::get_bar_point(self.cur_player);
-        let mut relevant_points = if !self.points[cur_player_bar].is_empty() {
-            vec![cur_player_bar]
-        } else {
-            self.used_points(self.cur_player)
-        };
-
-        if relevant_points.is_empty() {
-            return vec![];
-        }
-
-        Self::reverse_white_points(self.cur_player, &mut relevant_points);
-
-        let farthest = *relevant_points.iter().min().unwrap();
+    // whether all of player's checkers are in their home board, so that they
+    // may be borne off
+    fn is_bearing_off(&self, player: PlayerColor) -> bool {
+        (7..=25).all(|pip| {
+            self.checkers_at(player, Location::from_pip(pip).unwrap()) == 0
+        })
+    }
 
-        let bearing_off = farthest >= 19;
+    // check that a move is possible for the current player, without regard
+    // to the dice
+    pub fn check_move(&self, Move(from, to): Move) -> Result<(), MoveError> {
+        let player = self.cur_player;
 
-        let moves = relevant_points
-            .iter()
-            .map(|i| Move(*i, i + die_roll))
-            // destination must be empty or a blot, if on the board
-            .filter(|Move(_i, j)| {
-                // off the board is ok at this point
-                if *j > 24 {
-                    return true;
+        for &location in [from, to].iter() {
+            if let Location::Point(n) = location {
+                if !(1..=24).contains(&n) {
+                    return Err(MoveError::InvalidLocation);
                 }
+            }
+        }
 
-                let real_j = Self::reverse_white_point(self.cur_player, *j);
-                let end_point = &self.points[real_j];
-
-                // empty or used by current player is ok
-                if !end_point.is_used_by(self.cur_player.inverse()) {
-                    return true;
-                }
+        if from == Location::Off || self.checkers_at(player, from) == 0 {
+            return Err(MoveError::NoCheckerAtSource);
+        }
 
-                // else used by other player. if only 1 checker is present,
-                // also ok.
-                end_point.checker_count == 1
-            });
-
-        let mut moves = if bearing_off {
-            // destination allowed to be off the board, BUT must be either exact
-            // or else this is the farthest move. also, if it's off the board,
-            // let's fix the index.
-            moves
-                .filter_map(|move_| {
-                    let Move(i, j) = move_;
-
-                    // on the board, or exactly off the board
-                    if j <= 25 {
-                        Some(move_)
-                    } else if i == farthest {
-                        Some(Move(i, 25))
-                    } else {
-                        None
-                    }
-                }).collect()
-        } else {
-            // destination must be on board
-            moves.filter(|Move(_i, j)| *j <= 24).collect()
-        };
+        if from != Location::Bar && self.checkers_at(player, Location::Bar) > 0
+        {
+            return Err(MoveError::MustEnterFromBar);
+        }
 
-        // unswap indices before returning
-        Self::reverse_white_moves(self.cur_player, &mut moves);
-        moves
-    }
+        if to == Location::Bar || to.pip() >= from.pip() {
+            return Err(MoveError::WrongDirection);
+        }
 
-    // get index of fake point used as bar for player's piece. this is set up
-    // such that moves from bar is moving into the other player's home, using
-    // usual math.
-    pub fn get_bar_point(player: PlayerColor) -> PointIndex {
-        match player {
-            PlayerColor::Black => 0,
-            PlayerColor::White => 25,
+        if to == Location::Off {
+            if !self.is_bearing_off(player) {
+                return Err(MoveError::IllegalBearOff);
+            }
+        } else if !self.is_open_for(player, to) {
+            return Err(MoveError::BlockedDestination);
         }
-    }
 
-    pub fn is_bar_point(pi: PointIndex) -> bool {
-        pi < 1 || pi > 24
+        Ok(())
     }
 
-    pub fn apply_move(&mut self, Move(i, j): Move) {
-        assert!(self.points[i].is_used_by(self.cur_player));
-        self.points[i].checker_count -= 1;
+    pub fn try_apply_move(&mut self, move_: Move) -> Result<(), MoveError> {
+        self.check_move(move_)?;
+        self.make_move(move_);
+        Ok(())
+    }
 
-        if Self::is_bar_point(j) {
-            // just remove the checker and we're done
-            return;
+    // apply a sequence of moves. if any of them fails, the board is left as
+    // it was.
+    pub fn try_apply_move_seq<'a, T>(
+        &mut self,
+        move_seq: T,
+    ) -> Result<(), MoveError>
+    where
+        T: Iterator<Item = &'a Move>,
+    {
+        let mut new_state = self.clone();
+        for &move_ in move_seq {
+            new_state.try_apply_move(move_)?;
         }
 
-        if self.points[j].is_used_by(self.cur_player.inverse()) {
-            assert_eq!(self.points[j].checker_count, 1);
-
-            let bar_index = Self::get_bar_point(self.points[j].checker_color);
-            self.points[bar_index].checker_count += 1;
+        *self = new_state;
+        Ok(())
+    }
 
-            // clear checker count so that increasing by one works later
-            self.points[j].checker_count = 0;
+    // like try_apply_move(), but panics if the move isn't possible
+    pub fn apply_move(&mut self, move_: Move) {
+        if let Err(err) = self.try_apply_move(move_) {
+            panic!("can't apply {:?}: {}", move_, err);
         }
-
-        self.points[j].checker_color = self.cur_player;
-        self.points[j].checker_count += 1;
     }
 
     pub fn apply_move_seq<'a, T>(&mut self, move_seq: T)
@@ -351,120 +597,37 @@ impl BoardState {
         self.cur_player = self.cur_player.inverse();
     }
 
-    // helper function for get_moves(). generates list of possible move
-    // sequences for a given permutation of the dice.
-    fn backtrack_die_moves(&self, dice_slice: &[usize]) -> Vec<VecDeque<Move>> {
-        let first_moves = if dice_slice.is_empty() {
-            vec![]
-        } else {
-            self.get_moves_for_single_die(dice_slice[0])
-        };
-
-        let rest = &dice_slice[1..];
-        if rest.is_empty() {
-            first_moves
-                .iter()
-                .map(|&move_| {
-                    let mut v = VecDeque::new();
-                    v.push_back(move_);
-                    v
-                }).collect()
-        } else {
-            // if first_moves is empty then we don't get any moves! which means
-            // that we don't try the rest of the dice in the slice. BUT this
-            // function is called for every possible permutation, so if we don't
-            // return the right move here, it'll just be returned for the other
-            // permutation where the ones that work are first.
-            first_moves
-                .iter()
-                .flat_map(move |&move_| {
-                    let state_after_move = self.with_move(move_);
-                    let mut next_move_seqs =
-                        state_after_move.backtrack_die_moves(&dice_slice[1..]);
-
-                    for next_moves in next_move_seqs.iter_mut() {
-                        next_moves.push_front(move_);
-                    }
-
-                    // if next_move_seqs is empty (e.g. if performing all
-                    // moves is impossible) then we won't have gotten anything.
-                    // but player can still do the first move, so return that.
-                    if next_move_seqs.is_empty() {
-                        let mut seq = VecDeque::new();
-                        seq.push_back(move_);
-                        next_move_seqs.push(seq);
-                    }
-
-                    next_move_seqs
-                }).collect()
-        }
-    }
-
-    pub fn get_move_seqs(&self, dice_roll: (usize, usize)) -> Vec<Vec<Move>> {
-        // convert to vec + double doubles + generate all permutations
-        let is_double = dice_roll.0 == dice_roll.1;
-        let perms = if is_double {
-            vec![repeat(dice_roll.0).take(4).collect()]
-        } else {
-            vec![
-                vec![dice_roll.0, dice_roll.1],
-                vec![dice_roll.1, dice_roll.0],
-            ]
-        };
-
-        let move_seqs: Vec<Vec<Move>> = perms
-            .into_iter()
-            .flat_map(|perm| {
-                // convert to Vecs
-                self.backtrack_die_moves(&perm)
-                    .into_iter()
-                    .map(|move_seq| move_seq.into_iter().collect::<Vec<Move>>())
-            }).collect();
-
-        // get length of longest sequence
-        let max_seq_len = move_seqs.iter().map(|s| s.len()).max().unwrap_or(0);
-
-        // if only one of the dice can be used, prefer the larger one. this is
-        // relevant iff not a double, max_seq_len == 1, and both dice have
-        // possible moves. note that if max_seq_len == 1, then all the sequences
-        // are of length 1; they can't be empty.
-        if !is_double
-            && max_seq_len == 1
-            // note that moves are sorted by die roll so dedup is ok
-            && move_seqs.iter().map(|s| s[0].die_roll()).dedup().count() >= 2
-        {
-            let larger_die = max(dice_roll.0, dice_roll.1);
-            return move_seqs
-                .into_iter()
-                .filter(|s| s[0].die_roll() == larger_die)
-                .collect();
-        }
-
-        // else we must perform maximum number of moves
-        move_seqs
-            .into_iter()
-            .filter(|s| s.len() >= max_seq_len)
-            .collect()
+    pub fn get_move_seqs(&self, dice_roll: DiceRoll) -> Vec<Vec<Move>> {
+        let mut plays = vec![];
+        self.generate_plays(dice_roll, &mut plays);
+        plays.iter().map(|play| play.to_vec()).collect()
     }
 
     pub fn get_winner(&self) -> Option<PlayerColor> {
-        let mut black_won = true;
-        let mut white_won = true;
-        for point in self.points.iter() {
-            if !point.is_empty() {
-                match point.checker_color {
-                    PlayerColor::Black => black_won = false,
-                    PlayerColor::White => white_won = false,
-                }
-            }
-        }
-
-        if black_won && !white_won {
+        if self.black_borne_off == CHECKERS_PER_PLAYER {
             Some(PlayerColor::Black)
-        } else if white_won && !black_won {
+        } else if self.white_borne_off == CHECKERS_PER_PLAYER {
             Some(PlayerColor::White)
         } else {
             None
         }
     }
+
+    pub fn get_result(&self) -> Option<GameResult> {
+        let winner = self.get_winner()?;
+        let loser = winner.inverse();
+
+        let kind = if self.borne_off(loser) > 0 {
+            WinKind::Single
+        } else if (19..=25).any(|pip| {
+            // loser still has checkers on the bar or in winner's home board
+            self.checkers_at(loser, Location::from_pip(pip).unwrap()) > 0
+        }) {
+            WinKind::Backgammon
+        } else {
+            WinKind::Gammon
+        };
+
+        Some(GameResult::new(winner, kind))
+    }
 }
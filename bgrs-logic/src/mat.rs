@@ -0,0 +1,612 @@
+use super::{
+    Action, BoardState, CubeState, DiceRoll, Game, GameError, Location, Match,
+    Move, NotationError, Phase, PlayerColor, WinKind,
+};
+use std::error::Error;
+use std::fmt::{self, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MatErrorKind {
+    // the file should start with e.g. " 5 point match"
+    InvalidHeader,
+    // a line that isn't a game header, player names, moves or a result
+    InvalidLine,
+    InvalidDice,
+    // moves before the first game header
+    NoGame,
+    // a game after one of the players already won the match
+    MatchOver,
+    // a play that can't be read, or isn't legal or is ambiguous
+    InvalidPlay(NotationError),
+    // e.g. rolling when a double has to be answered
+    InvalidAction(GameError),
+    // the winner or the number of points won doesn't match the game
+    WrongResult,
+    // the score before a game doesn't match the games before it
+    WrongScore,
+    // e.g. "Doubles => 4" when the cube is at 1
+    WrongCubeValue,
+}
+
+impl fmt::Display for MatErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MatErrorKind::InvalidHeader => write!(f, "invalid match header"),
+            MatErrorKind::InvalidLine => write!(f, "can't parse line"),
+            MatErrorKind::InvalidDice => write!(f, "invalid dice"),
+            MatErrorKind::NoGame => write!(f, "moves before the first game"),
+            MatErrorKind::MatchOver => write!(f, "match is already over"),
+            MatErrorKind::InvalidPlay(err) => write!(f, "{}", err),
+            MatErrorKind::InvalidAction(err) => write!(f, "{}", err),
+            MatErrorKind::WrongResult => {
+                write!(f, "points won don't match the game")
+            }
+            MatErrorKind::WrongScore => {
+                write!(f, "score doesn't match the games before")
+            }
+            MatErrorKind::WrongCubeValue => {
+                write!(f, "cube value doesn't match the game")
+            }
+        }
+    }
+}
+
+impl From<NotationError> for MatErrorKind {
+    fn from(err: NotationError) -> Self {
+        MatErrorKind::InvalidPlay(err)
+    }
+}
+
+impl From<GameError> for MatErrorKind {
+    fn from(err: GameError) -> Self {
+        MatErrorKind::InvalidAction(err)
+    }
+}
+
+// a problem in a .mat file, and the line number it's on, starting from 1
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatError {
+    pub line: usize,
+    pub kind: MatErrorKind,
+}
+
+impl fmt::Display for MatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl Error for MatError {}
+
+// where each column starts in a line of a game, after the move number
+const LEFT_COLUMN: usize = 5;
+const RIGHT_COLUMN: usize = 33;
+
+// a match, or a session of money games, as saved in Jellyfish's .mat format,
+// which gnubg and XG also read. black is written as the first player, in the
+// left column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchRecord {
+    // 0 for money games
+    pub length: usize,
+    pub black_name: String,
+    pub white_name: String,
+    pub games: Vec<Game>,
+}
+
+// a play as it appears in .mat files, with each move written separately and
+// bar and off as 25 and 0, e.g. "25/22 13/11*"
+fn format_mat_play(board: &BoardState, move_seq: &[Move]) -> String {
+    let opponent = board.cur_player.inverse();
+    let mut board = board.clone();
+
+    let moves: Vec<String> = move_seq
+        .iter()
+        .map(|&move_| {
+            let opponent_bar = board.checkers_at(opponent, Location::Bar);
+            board.apply_move(move_);
+            let hit = board.checkers_at(opponent, Location::Bar) > opponent_bar;
+
+            format!(
+                "{}/{}{}",
+                move_.0.pip(),
+                move_.1.pip(),
+                if hit { "*" } else { "" }
+            )
+        })
+        .collect();
+
+    moves.join(" ")
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+// what each player did in a game, in order, as .mat text
+fn game_entries(game: &Game) -> Vec<(PlayerColor, String)> {
+    let history = game.history();
+
+    // the cube's value before any doubles, e.g. from automatic doubles
+    let takes = history
+        .iter()
+        .filter(|(_, action)| *action == Action::Take)
+        .count();
+    let beavers = history
+        .iter()
+        .filter(|(_, action)| *action == Action::Beaver)
+        .count();
+    let mut cube_value = game.cube().value >> (takes + 2 * beavers);
+
+    let mut board = match game.setup() {
+        Some(setup) => setup.board.clone(),
+        None => BoardState::new_starting_state(PlayerColor::Black),
+    };
+    let mut dice = (0, 0);
+    let mut entries = vec![];
+
+    for (player, action) in history {
+        let player = *player;
+        let entry = match action {
+            Action::OpeningRoll(black_die, white_die) => {
+                // the winner's die is written first
+                dice = if black_die > white_die {
+                    (*black_die, *white_die)
+                } else {
+                    (*white_die, *black_die)
+                };
+                continue;
+            }
+            Action::Roll(roll) => {
+                dice = *roll;
+                continue;
+            }
+            Action::Move(move_seq) => {
+                board.cur_player = player;
+                let play = format_mat_play(&board, move_seq);
+                board.apply_move_seq(move_seq.iter());
+                format!("{}{}: {}", dice.0, dice.1, play)
+            }
+            Action::Double => format!(" Doubles => {}", cube_value * 2),
+            Action::Take => {
+                cube_value *= 2;
+                " Takes".to_string()
+            }
+            Action::Drop => " Drops".to_string(),
+            Action::Beaver => {
+                cube_value *= 4;
+                format!(" Beavers => {}", cube_value)
+            }
+            // only the points won are written, after the game
+            Action::Resign(_) => continue,
+        };
+
+        entries.push((player, entry));
+    }
+
+    entries
+}
+
+// words that start an entry in a game, other than dice
+const CUBE_ACTIONS: &[&str] = &[
+    "Doubles", "Takes", "Accepts", "Drops", "Passes", "Rejects", "Beavers",
+];
+
+fn parse_dice(word: &str) -> Option<DiceRoll> {
+    let digits: Vec<usize> = word
+        .strip_suffix(':')?
+        .chars()
+        .map(|c| c.to_digit(10).map_or(0, |d| d as usize))
+        .collect();
+
+    match *digits.as_slice() {
+        [die_1, die_2]
+            if (1..=6).contains(&die_1) && (1..=6).contains(&die_2) =>
+        {
+            Some((die_1, die_2))
+        }
+        _ => None,
+    }
+}
+
+// e.g. " 5 point match"
+fn parse_header(line: &str) -> Option<usize> {
+    match *line.split_whitespace().collect::<Vec<&str>>().as_slice() {
+        [length, "point", "match"] => length.parse().ok(),
+        _ => None,
+    }
+}
+
+// the names and scores in e.g. " alice : 0       bob : 2"
+fn parse_names(line: &str) -> Option<((String, usize), (String, usize))> {
+    let parts: Vec<&str> = line.split(':').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+
+    let middle = parts[1].trim();
+    let score_end = middle.find(char::is_whitespace)?;
+    let black_score = middle[..score_end].parse().ok()?;
+    let white_score = parts[2].trim().parse().ok()?;
+
+    Some((
+        (parts[0].trim().to_string(), black_score),
+        (middle[score_end..].trim().to_string(), white_score),
+    ))
+}
+
+// e.g. "Wins 2 points", or "Wins 1 point and the match" for the last game
+// of a match
+fn parse_wins(line: &str) -> Option<usize> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let words = match *words.as_slice() {
+        [ref rest @ .., "and", "the", "match"] => rest,
+        ref words => words,
+    };
+
+    match *words {
+        ["Wins", points, "point"] | ["Wins", points, "points"] => {
+            points.parse().ok()
+        }
+        _ => None,
+    }
+}
+
+// split the rest of a numbered line into entries, each starting with dice or
+// a cube action
+fn split_entries(rest: &str) -> Option<Vec<Vec<&str>>> {
+    let mut entries: Vec<Vec<&str>> = vec![];
+    for word in rest.split_whitespace() {
+        if word.ends_with(':') || CUBE_ACTIONS.contains(&word) {
+            entries.push(vec![word]);
+        } else {
+            entries.last_mut()?.push(word);
+        }
+    }
+
+    Some(entries)
+}
+
+// check the new cube value in an entry like "Doubles => 2". the value can be
+// left out, but if it's there it has to match.
+fn check_cube_value(entry: &[&str], value: usize) -> Result<(), MatErrorKind> {
+    match entry[1..] {
+        [] => Ok(()),
+        ["=>", new_value] => match new_value.parse::<usize>() {
+            Ok(new_value) if new_value == value => Ok(()),
+            Ok(_) => Err(MatErrorKind::WrongCubeValue),
+            Err(_) => Err(MatErrorKind::InvalidLine),
+        },
+        _ => Err(MatErrorKind::InvalidLine),
+    }
+}
+
+// apply one player's entry, e.g. "52: 13/8 13/11" or "Doubles => 2".
+// opening_player is only used if this is the opening roll.
+fn apply_entry(
+    game: &mut Game,
+    entry: &[&str],
+    opening_player: PlayerColor,
+) -> Result<(), MatErrorKind> {
+    let player = game.player_to_act().unwrap_or(opening_player);
+
+    let action = match entry[0] {
+        "Doubles" => {
+            check_cube_value(entry, game.cube().value * 2)?;
+            Action::Double
+        }
+        "Takes" | "Accepts" => Action::Take,
+        "Drops" | "Passes" | "Rejects" => Action::Drop,
+        "Beavers" => {
+            check_cube_value(entry, game.cube().value * 4)?;
+            Action::Beaver
+        }
+        word => {
+            let dice = parse_dice(word).ok_or(MatErrorKind::InvalidDice)?;
+
+            if game.phase() == Phase::OpeningRoll {
+                // the winner's die is usually written first, but either
+                // order is fine
+                let (high, low) = (dice.0.max(dice.1), dice.0.min(dice.1));
+                let opening_roll = match opening_player {
+                    PlayerColor::Black => Action::OpeningRoll(high, low),
+                    PlayerColor::White => Action::OpeningRoll(low, high),
+                };
+                game.apply(player, opening_roll)?;
+            } else {
+                game.apply(player, Action::Roll(dice))?;
+            }
+
+            let board = game.board();
+            let dice = match game.phase() {
+                Phase::AwaitingMove(dice) => dice,
+                _ => return Err(GameError::WrongPhase.into()),
+            };
+
+            let move_seq = board.parse_play(dice, &entry[1..].join(" "))?;
+            Action::Move(move_seq)
+        }
+    };
+
+    let player = game.player_to_act().unwrap_or(player);
+    game.apply(player, action)?;
+    Ok(())
+}
+
+fn pad_to(line: &mut String, column: usize) {
+    while line.len() < column {
+        line.push(' ');
+    }
+}
+
+impl MatchRecord {
+    pub fn new(length: usize, black_name: &str, white_name: &str) -> Self {
+        Self {
+            length,
+            black_name: black_name.to_string(),
+            white_name: white_name.to_string(),
+            games: vec![],
+        }
+    }
+
+    pub fn to_mat(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, " {} point match", self.length).unwrap();
+        writeln!(out).unwrap();
+
+        let mut black_score = 0;
+        let mut white_score = 0;
+
+        for (i, game) in self.games.iter().enumerate() {
+            writeln!(out, " Game {}", i + 1).unwrap();
+
+            let mut line = format!(" {} : {}", self.black_name, black_score);
+            pad_to(&mut line, RIGHT_COLUMN);
+            writeln!(out, "{}{} : {}", line, self.white_name, white_score)
+                .unwrap();
+
+            // black's entries go on the left and white's on the right, and
+            // a line is numbered even if it only has one of them
+            let mut line_count = 0;
+            let mut line = String::new();
+            for (player, entry) in game_entries(game) {
+                if player == PlayerColor::Black && !line.is_empty() {
+                    writeln!(out, "{}", line.trim_end()).unwrap();
+                    line.clear();
+                }
+
+                if line.is_empty() {
+                    line_count += 1;
+                    line = format!("{:3}) ", line_count);
+                }
+
+                pad_to(
+                    &mut line,
+                    match player {
+                        PlayerColor::Black => LEFT_COLUMN,
+                        PlayerColor::White => RIGHT_COLUMN,
+                    },
+                );
+                // long plays may run into the right column
+                if !line.ends_with(' ') {
+                    line.push(' ');
+                }
+                line.push_str(&entry);
+
+                if player == PlayerColor::White {
+                    writeln!(out, "{}", line.trim_end()).unwrap();
+                    line.clear();
+                }
+            }
+
+            if !line.is_empty() {
+                writeln!(out, "{}", line.trim_end()).unwrap();
+            }
+
+            if let (Some(result), Some(points)) = (game.result(), game.points())
+            {
+                let mut line = String::new();
+                pad_to(
+                    &mut line,
+                    match result.winner {
+                        PlayerColor::Black => LEFT_COLUMN + 1,
+                        PlayerColor::White => RIGHT_COLUMN + 1,
+                    },
+                );
+                let score = match result.winner {
+                    PlayerColor::Black => &mut black_score,
+                    PlayerColor::White => &mut white_score,
+                };
+                *score += points;
+                let match_over = self.length > 0 && *score >= self.length;
+
+                writeln!(
+                    out,
+                    "{}Wins {} point{}{}",
+                    line,
+                    points,
+                    plural(points),
+                    if match_over { " and the match" } else { "" }
+                )
+                .unwrap();
+            }
+
+            writeln!(out).unwrap();
+        }
+
+        out
+    }
+
+    // points won by black and white in the games so far
+    fn scores(&self) -> (usize, usize) {
+        let mut scores = (0, 0);
+        for game in &self.games {
+            if let (Some(result), Some(points)) = (game.result(), game.points())
+            {
+                match result.winner {
+                    PlayerColor::Black => scores.0 += points,
+                    PlayerColor::White => scores.1 += points,
+                }
+            }
+        }
+
+        scores
+    }
+
+    // read a .mat file, replaying each game and checking that every play is
+    // legal. a game that ends before it's finished, with only the points won,
+    // was resigned.
+    pub fn from_mat(text: &str) -> Result<Self, MatError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            // the indentation is kept, as it shows who won a game
+            .map(|(i, line)| (i + 1, line.trim_end()))
+            // skip blank lines and comments
+            .filter(|(_, line)| {
+                let line = line.trim_start();
+                !line.is_empty() && !line.starts_with(';')
+            });
+
+        let (line_number, header) = lines.next().unwrap_or((1, ""));
+        let length = parse_header(header).ok_or(MatError {
+            line: line_number,
+            kind: MatErrorKind::InvalidHeader,
+        })?;
+
+        let mut record = Self::new(length, "", "");
+        let mut match_ = if length > 0 {
+            Some(Match::new(length))
+        } else {
+            None
+        };
+        let mut game: Option<Game> = None;
+
+        while let Some((line_number, full_line)) = lines.next() {
+            let error = |kind| MatError {
+                line: line_number,
+                kind,
+            };
+            let line = full_line.trim_start();
+
+            if line.starts_with("Game ") {
+                if let Some(game) = game.take() {
+                    record.add_game(game, match_.as_mut());
+                }
+
+                game = Some(match match_.as_mut() {
+                    Some(match_) if match_.is_over() => {
+                        return Err(error(MatErrorKind::MatchOver))
+                    }
+                    Some(match_) => match_.start_game(),
+                    None => Game::new_with_opening_roll(CubeState::new(), 0),
+                });
+
+                // the players' names and scores come next
+                let (line_number, line) =
+                    lines.next().unwrap_or((line_number, ""));
+                let error = |kind| MatError {
+                    line: line_number,
+                    kind,
+                };
+                let ((black_name, black_score), (white_name, white_score)) =
+                    parse_names(line)
+                        .ok_or(error(MatErrorKind::InvalidLine))?;
+
+                if (black_score, white_score) != record.scores() {
+                    return Err(error(MatErrorKind::WrongScore));
+                }
+
+                if record.games.is_empty() {
+                    record.black_name = black_name;
+                    record.white_name = white_name;
+                }
+
+                continue;
+            }
+
+            let cur_game = game.as_mut().ok_or(error(MatErrorKind::NoGame))?;
+
+            if line.starts_with("Wins") {
+                let points =
+                    parse_wins(line).ok_or(error(MatErrorKind::InvalidLine))?;
+
+                // written under the winner's column
+                let indent = full_line.len() - line.len();
+                let winner = if indent > LEFT_COLUMN + 1 {
+                    PlayerColor::White
+                } else {
+                    PlayerColor::Black
+                };
+
+                if cur_game.result().is_none() {
+                    // the loser resigned, conceding the points
+                    let value = cur_game.cube().value;
+                    let kind = Some(points)
+                        .filter(|points| points % value == 0)
+                        .and_then(|points| {
+                            WinKind::from_multiplier(points / value)
+                        })
+                        .ok_or(error(MatErrorKind::WrongResult))?;
+                    cur_game
+                        .apply(winner.inverse(), Action::Resign(kind))
+                        .map_err(|err| error(err.into()))?;
+                }
+
+                let result = cur_game.result().unwrap();
+                if result.winner != winner || cur_game.points() != Some(points)
+                {
+                    return Err(error(MatErrorKind::WrongResult));
+                }
+
+                continue;
+            }
+
+            // e.g. "12) 52: 13/8 13/11      64: 24/18 13/9"
+            let rest = match line.find(')') {
+                Some(end) if line[..end].parse::<usize>().is_ok() => {
+                    &line[end + 1..]
+                }
+                _ => return Err(error(MatErrorKind::InvalidLine)),
+            };
+
+            let entries =
+                split_entries(rest).ok_or(error(MatErrorKind::InvalidLine))?;
+
+            // black's entries are on the left and white's on the right. this
+            // only matters for the opening roll, as after that the players
+            // take turns.
+            let indent = rest.len() - rest.trim_start().len();
+            let first_player = if entries.len() == 1 && indent > LEFT_COLUMN {
+                PlayerColor::White
+            } else {
+                PlayerColor::Black
+            };
+
+            for entry in entries {
+                apply_entry(cur_game, &entry, first_player).map_err(error)?;
+            }
+        }
+
+        if let Some(game) = game {
+            record.add_game(game, match_.as_mut());
+        }
+
+        Ok(record)
+    }
+
+    pub(crate) fn add_game(&mut self, game: Game, match_: Option<&mut Match>) {
+        if let (Some(match_), Some(result)) = (match_, game.result()) {
+            match_.record_result(&result, game.cube());
+        }
+
+        self.games.push(game);
+    }
+}
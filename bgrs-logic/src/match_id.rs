@@ -0,0 +1,314 @@
+use super::position_id::{decode_base64, encode_base64};
+use super::{
+    Action, BoardState, CubeState, DiceRoll, Game, Match, Phase, PlayerColor,
+    Setup, WinKind,
+};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MatchIdError {
+    InvalidLength,
+    InvalidCharacter(char),
+    InvalidCubeOwner,
+    InvalidGameState,
+    InvalidDice,
+}
+
+impl fmt::Display for MatchIdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MatchIdError::InvalidLength => {
+                write!(f, "match id must be 12 characters long")
+            }
+            MatchIdError::InvalidCharacter(c) => {
+                write!(f, "invalid character '{}' in match id", c)
+            }
+            MatchIdError::InvalidCubeOwner => write!(f, "invalid cube owner"),
+            MatchIdError::InvalidGameState => write!(f, "invalid game state"),
+            MatchIdError::InvalidDice => write!(f, "invalid dice"),
+        }
+    }
+}
+
+impl Error for MatchIdError {}
+
+// the states of a game that gnubg distinguishes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MatchIdGameState {
+    NoGame,
+    Playing,
+    GameOver,
+    Resigned,
+    // game ended because a double was dropped
+    DoubleDropped,
+}
+
+// everything in gnubg's match id. gnubg calls white player 0, and black
+// player 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchId {
+    pub cube_value: usize,
+    // None while the cube is centered
+    pub cube_owner: Option<PlayerColor>,
+    // player on roll, whose point of view the position id is from
+    pub player_on_roll: PlayerColor,
+    pub crawford: bool,
+    pub game_state: MatchIdGameState,
+    // player who has to make the next decision, e.g. to take a double
+    pub turn: PlayerColor,
+    pub double_offered: bool,
+    pub resignation: Option<WinKind>,
+    // None if the dice haven't been rolled yet
+    pub dice: Option<DiceRoll>,
+    // 0 for money games
+    pub match_length: usize,
+    pub black_score: usize,
+    pub white_score: usize,
+}
+
+const MATCH_ID_BYTES: usize = 9;
+const MATCH_ID_LEN: usize = 12;
+
+// bit fields, as (offset, width). each field is stored starting from its
+// lowest bit, and bits are filled in from the lowest bit of each byte.
+const CUBE_VALUE: (usize, usize) = (0, 4);
+const CUBE_OWNER: (usize, usize) = (4, 2);
+const PLAYER_ON_ROLL: (usize, usize) = (6, 1);
+const CRAWFORD: (usize, usize) = (7, 1);
+const GAME_STATE: (usize, usize) = (8, 3);
+const TURN: (usize, usize) = (11, 1);
+const DOUBLE_OFFERED: (usize, usize) = (12, 1);
+const RESIGNATION: (usize, usize) = (13, 2);
+const DIE_1: (usize, usize) = (15, 3);
+const DIE_2: (usize, usize) = (18, 3);
+const MATCH_LENGTH: (usize, usize) = (21, 15);
+const WHITE_SCORE: (usize, usize) = (36, 15);
+const BLACK_SCORE: (usize, usize) = (51, 15);
+
+fn set_field(bytes: &mut [u8], (offset, width): (usize, usize), value: usize) {
+    for i in 0..width {
+        if value & (1 << i) != 0 {
+            let bit = offset + i;
+            bytes[bit / 8] |= 1 << (bit % 8);
+        }
+    }
+}
+
+fn get_field(bytes: &[u8], (offset, width): (usize, usize)) -> usize {
+    (0..width)
+        .filter(|i| {
+            let bit = offset + i;
+            bytes[bit / 8] & (1 << (bit % 8)) != 0
+        })
+        .fold(0, |value, i| value | 1 << i)
+}
+
+fn player_number(player: PlayerColor) -> usize {
+    match player {
+        PlayerColor::White => 0,
+        PlayerColor::Black => 1,
+    }
+}
+
+fn player_from_number(number: usize) -> PlayerColor {
+    if number == 0 {
+        PlayerColor::White
+    } else {
+        PlayerColor::Black
+    }
+}
+
+impl MatchId {
+    // state of a game, which may be part of a match
+    pub fn from_game(game: &Game, match_: Option<&Match>) -> Self {
+        let board = game.board();
+        let cube = game.cube();
+
+        let game_state = match game.phase() {
+            Phase::OpeningRoll => MatchIdGameState::NoGame,
+            Phase::GameOver(_) => match game.history().last() {
+                Some((_, Action::Drop)) => MatchIdGameState::DoubleDropped,
+                _ => MatchIdGameState::GameOver,
+            },
+            _ => MatchIdGameState::Playing,
+        };
+
+        let dice = match game.phase() {
+            Phase::AwaitingMove(dice) => Some(dice),
+            _ => None,
+        };
+
+        Self {
+            cube_value: cube.value,
+            cube_owner: cube.owner,
+            player_on_roll: board.cur_player,
+            crawford: match_.is_some_and(|m| m.crawford),
+            game_state,
+            turn: game.player_to_act().unwrap_or(board.cur_player),
+            double_offered: game.phase() == Phase::CubeDecision,
+            resignation: None,
+            dice,
+            match_length: match_.map_or(0, |m| m.length),
+            black_score: match_.map_or(0, |m| m.black_score),
+            white_score: match_.map_or(0, |m| m.white_score),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let mut bytes = [0u8; MATCH_ID_BYTES];
+
+        set_field(
+            &mut bytes,
+            CUBE_VALUE,
+            self.cube_value.trailing_zeros() as usize,
+        );
+        set_field(
+            &mut bytes,
+            CUBE_OWNER,
+            self.cube_owner.map_or(3, player_number),
+        );
+        set_field(
+            &mut bytes,
+            PLAYER_ON_ROLL,
+            player_number(self.player_on_roll),
+        );
+        set_field(&mut bytes, CRAWFORD, self.crawford as usize);
+        set_field(&mut bytes, GAME_STATE, self.game_state as usize);
+        set_field(&mut bytes, TURN, player_number(self.turn));
+        set_field(&mut bytes, DOUBLE_OFFERED, self.double_offered as usize);
+        // resignations are stored as the number of points resigned
+        set_field(
+            &mut bytes,
+            RESIGNATION,
+            self.resignation.map_or(0, WinKind::multiplier),
+        );
+
+        let (die_1, die_2) = self.dice.unwrap_or((0, 0));
+        set_field(&mut bytes, DIE_1, die_1);
+        set_field(&mut bytes, DIE_2, die_2);
+
+        set_field(&mut bytes, MATCH_LENGTH, self.match_length);
+        set_field(&mut bytes, WHITE_SCORE, self.white_score);
+        set_field(&mut bytes, BLACK_SCORE, self.black_score);
+
+        encode_base64(&bytes)
+    }
+
+    pub fn decode(id: &str) -> Result<Self, MatchIdError> {
+        if id.chars().count() != MATCH_ID_LEN {
+            return Err(MatchIdError::InvalidLength);
+        }
+
+        let bytes =
+            decode_base64(id).map_err(MatchIdError::InvalidCharacter)?;
+
+        let cube_owner = match get_field(&bytes, CUBE_OWNER) {
+            3 => None,
+            2 => return Err(MatchIdError::InvalidCubeOwner),
+            number => Some(player_from_number(number)),
+        };
+
+        let game_state = match get_field(&bytes, GAME_STATE) {
+            0 => MatchIdGameState::NoGame,
+            1 => MatchIdGameState::Playing,
+            2 => MatchIdGameState::GameOver,
+            3 => MatchIdGameState::Resigned,
+            4 => MatchIdGameState::DoubleDropped,
+            _ => return Err(MatchIdError::InvalidGameState),
+        };
+
+        let resignation = match get_field(&bytes, RESIGNATION) {
+            0 => None,
+            1 => Some(WinKind::Single),
+            2 => Some(WinKind::Gammon),
+            _ => Some(WinKind::Backgammon),
+        };
+
+        let dice = match (get_field(&bytes, DIE_1), get_field(&bytes, DIE_2)) {
+            (0, 0) => None,
+            (die_1, die_2)
+                if (1..=6).contains(&die_1) && (1..=6).contains(&die_2) =>
+            {
+                Some((die_1, die_2))
+            }
+            _ => return Err(MatchIdError::InvalidDice),
+        };
+
+        Ok(Self {
+            cube_value: 1 << get_field(&bytes, CUBE_VALUE),
+            cube_owner,
+            player_on_roll: player_from_number(get_field(
+                &bytes,
+                PLAYER_ON_ROLL,
+            )),
+            crawford: get_field(&bytes, CRAWFORD) != 0,
+            game_state,
+            turn: player_from_number(get_field(&bytes, TURN)),
+            double_offered: get_field(&bytes, DOUBLE_OFFERED) != 0,
+            resignation,
+            dice,
+            match_length: get_field(&bytes, MATCH_LENGTH),
+            black_score: get_field(&bytes, BLACK_SCORE),
+            white_score: get_field(&bytes, WHITE_SCORE),
+        })
+    }
+
+    // the match this id is part of, or None for money games
+    pub fn to_match(&self) -> Option<Match> {
+        if self.match_length == 0 {
+            return None;
+        }
+
+        Some(Match::resume(
+            self.match_length,
+            self.black_score,
+            self.white_score,
+            self.crawford,
+        ))
+    }
+
+    pub fn to_cube(&self) -> CubeState {
+        CubeState {
+            value: self.cube_value,
+            owner: self.cube_owner,
+            offered_by: if self.double_offered {
+                Some(self.player_on_roll)
+            } else {
+                None
+            },
+            enabled: !self.crawford,
+            beavers_allowed: self.match_length == 0,
+        }
+    }
+
+    // the game this id describes, given the board from the matching position
+    // id. returns None if the game has already ended, as the id doesn't say
+    // how.
+    pub fn to_game(&self, mut board: BoardState) -> Option<Game> {
+        board.cur_player = self.player_on_roll;
+
+        let phase = match self.game_state {
+            MatchIdGameState::NoGame => Phase::OpeningRoll,
+            MatchIdGameState::Playing => {
+                if self.double_offered {
+                    Phase::CubeDecision
+                } else if let Some(dice) = self.dice {
+                    Phase::AwaitingMove(dice)
+                } else {
+                    Phase::AwaitingRoll
+                }
+            }
+            _ => return None,
+        };
+
+        Some(Game::from_setup(Setup {
+            board,
+            cube: self.to_cube(),
+            phase,
+        }))
+    }
+}
@@ -0,0 +1,124 @@
+use super::{CubeState, Game, GameResult, PlayerColor};
+
+// a match played to a given number of points
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Match {
+    pub length: usize,
+    pub black_score: usize,
+    pub white_score: usize,
+    // number of games started so far
+    pub game_count: usize,
+    // whether the current game is the crawford game, i.e. the first game after
+    // one of the players got within a point of winning the match
+    pub crawford: bool,
+    // whether the crawford game has already been played
+    pub post_crawford: bool,
+}
+
+impl Match {
+    pub fn new(length: usize) -> Self {
+        assert!(length > 0);
+
+        Self {
+            length,
+            black_score: 0,
+            white_score: 0,
+            game_count: 0,
+            crawford: false,
+            post_crawford: false,
+        }
+    }
+
+    // a match that's already in progress, e.g. one read from a position
+    // format that only has the score. the number of games played is unknown.
+    pub(crate) fn resume(
+        length: usize,
+        black_score: usize,
+        white_score: usize,
+        crawford: bool,
+    ) -> Self {
+        let mut ret = Self::new(length);
+        ret.black_score = black_score;
+        ret.white_score = white_score;
+        ret.crawford = crawford;
+        // if someone is already 1 away and this isn't the crawford game, then
+        // the crawford game must be over
+        ret.post_crawford = !crawford
+            && (ret.away(PlayerColor::Black) == 1
+                || ret.away(PlayerColor::White) == 1);
+        ret
+    }
+
+    pub fn score(&self, player: PlayerColor) -> usize {
+        match player {
+            PlayerColor::Black => self.black_score,
+            PlayerColor::White => self.white_score,
+        }
+    }
+
+    fn score_mut(&mut self, player: PlayerColor) -> &mut usize {
+        match player {
+            PlayerColor::Black => &mut self.black_score,
+            PlayerColor::White => &mut self.white_score,
+        }
+    }
+
+    // number of points player still needs to win the match
+    pub fn away(&self, player: PlayerColor) -> usize {
+        self.length.saturating_sub(self.score(player))
+    }
+
+    pub fn get_winner(&self) -> Option<PlayerColor> {
+        [PlayerColor::Black, PlayerColor::White]
+            .iter()
+            .cloned()
+            .find(|&player| self.away(player) == 0)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.get_winner().is_some()
+    }
+
+    // cube for a new game. the cube can't be used in the crawford game, and
+    // beavers are only played for money.
+    pub fn new_cube(&self) -> CubeState {
+        CubeState {
+            enabled: !self.crawford,
+            beavers_allowed: false,
+            ..CubeState::new()
+        }
+    }
+
+    // start the next game of the match. the opening roll decides who starts,
+    // and automatic doubles aren't used in match play.
+    pub fn start_game(&mut self) -> Game {
+        assert!(!self.is_over());
+
+        self.game_count += 1;
+        Game::new_with_opening_roll(self.new_cube(), 0)
+    }
+
+    // add the result of the current game to the score, and work out whether
+    // the next game is the crawford game. returns the number of points won.
+    pub fn record_result(
+        &mut self,
+        result: &GameResult,
+        cube: &CubeState,
+    ) -> usize {
+        let points = cube.points(result);
+        *self.score_mut(result.winner) += points;
+
+        if self.crawford {
+            self.crawford = false;
+            self.post_crawford = true;
+        } else if !self.post_crawford
+            && !self.is_over()
+            && self.away(result.winner) == 1
+        {
+            self.crawford = true;
+        }
+
+        points
+    }
+}
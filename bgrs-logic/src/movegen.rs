@@ -0,0 +1,250 @@
+use super::{
+    BoardState, DiceRoll, DieRoll, Location, Move, PlayerColor,
+    CHECKERS_PER_PLAYER,
+};
+use std::ops::Deref;
+
+// a player makes at most 4 moves in a turn, when rolling a double
+pub const MAX_PLAY_LEN: usize = 4;
+
+// a play stored inline, so generating plays doesn't allocate each of them
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PlayBuf {
+    moves: [Move; MAX_PLAY_LEN],
+    len: usize,
+}
+
+impl Default for PlayBuf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlayBuf {
+    pub fn new() -> Self {
+        Self {
+            moves: [Move(Location::Off, Location::Off); MAX_PLAY_LEN],
+            len: 0,
+        }
+    }
+
+    pub fn push(&mut self, move_: Move) {
+        self.moves[self.len] = move_;
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<Move> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            Some(self.moves[self.len])
+        }
+    }
+
+    pub fn as_slice(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+}
+
+impl Deref for PlayBuf {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        self.as_slice()
+    }
+}
+
+// at most one move per point that the player is using
+type SingleDieMoves = ([Move; CHECKERS_PER_PLAYER], usize);
+
+impl BoardState {
+    // index into points of a location at pip distance from being borne off
+    fn pip_index(player: PlayerColor, pip: usize) -> usize {
+        match player {
+            PlayerColor::Black => 25 - pip,
+            PlayerColor::White => pip,
+        }
+    }
+
+    // like checkers_at(), but for locations on the board given by pip
+    fn count_at_pip(&self, player: PlayerColor, pip: usize) -> usize {
+        let point = &self.points[Self::pip_index(player, pip)];
+        if point.checker_color == player {
+            point.checker_count
+        } else {
+            0
+        }
+    }
+
+    // moves for a single die, farthest checkers first, ignoring the other
+    // dice. see get_moves_for_single_die().
+    pub(crate) fn single_die_moves(&self, die_roll: DieRoll) -> SingleDieMoves {
+        let player = self.cur_player;
+        let opponent = player.inverse();
+
+        let mut moves =
+            [Move(Location::Off, Location::Off); CHECKERS_PER_PLAYER];
+        let mut count = 0;
+
+        // must enter checkers on bar if possible
+        let (lowest_pip, highest_pip) = if self.count_at_pip(player, 25) > 0 {
+            (25, 25)
+        } else {
+            (1, 24)
+        };
+
+        let mut farthest = None;
+        for from_pip in (lowest_pip..=highest_pip).rev() {
+            if self.count_at_pip(player, from_pip) == 0 {
+                continue;
+            }
+
+            let farthest = *farthest.get_or_insert(from_pip);
+            let from = Location::from_pip(from_pip).unwrap();
+
+            if die_roll < from_pip {
+                let to_pip = from_pip - die_roll;
+                // destination must be empty, used by player or a blot
+                if self.count_at_pip(opponent, 25 - to_pip) < 2 {
+                    moves[count] = Move(from, Location::Point(to_pip));
+                    count += 1;
+                }
+            } else if farthest <= 6
+                && (die_roll == from_pip || from_pip == farthest)
+            {
+                // bearing off must be either exact, or else this is the
+                // farthest checker
+                moves[count] = Move(from, Location::Off);
+                count += 1;
+            }
+        }
+
+        (moves, count)
+    }
+
+    // apply a move without checking it, and return whether it hit a blot.
+    // undo it with unmake_move().
+    pub(crate) fn make_move(&mut self, Move(from, to): Move) -> bool {
+        let player = self.cur_player;
+        let opponent = player.inverse();
+
+        self.points[Self::pip_index(player, from.pip())].checker_count -= 1;
+
+        if to == Location::Off {
+            *self.borne_off_mut(player) += 1;
+            return false;
+        }
+
+        let point = &mut self.points[Self::pip_index(player, to.pip())];
+        let hit = point.is_used_by(opponent);
+        if hit {
+            point.checker_count = 0;
+        }
+
+        point.checker_color = player;
+        point.checker_count += 1;
+
+        if hit {
+            self.points[Self::pip_index(opponent, 25)].checker_count += 1;
+        }
+
+        hit
+    }
+
+    pub(crate) fn unmake_move(&mut self, Move(from, to): Move, hit: bool) {
+        let player = self.cur_player;
+        let opponent = player.inverse();
+
+        if to == Location::Off {
+            *self.borne_off_mut(player) -= 1;
+        } else {
+            let point = &mut self.points[Self::pip_index(player, to.pip())];
+            point.checker_count -= 1;
+
+            if hit {
+                point.checker_color = opponent;
+                point.checker_count = 1;
+                self.points[Self::pip_index(opponent, 25)].checker_count -= 1;
+            }
+        }
+
+        let point = &mut self.points[Self::pip_index(player, from.pip())];
+        point.checker_color = player;
+        point.checker_count += 1;
+    }
+
+    // depth first search over the moves for each die in turn. a play is
+    // output once all dice are used, or if the next die can't be used.
+    // returns whether any move was possible for the first die.
+    fn search_plays(
+        &mut self,
+        dice: &[DieRoll],
+        cur_play: &mut PlayBuf,
+        plays: &mut Vec<PlayBuf>,
+    ) -> bool {
+        let (moves, count) = self.single_die_moves(dice[0]);
+
+        for &move_ in &moves[..count] {
+            cur_play.push(move_);
+
+            let hit = self.make_move(move_);
+            if dice.len() == 1
+                || !self.search_plays(&dice[1..], cur_play, plays)
+            {
+                plays.push(*cur_play);
+            }
+            self.unmake_move(move_, hit);
+
+            cur_play.pop();
+        }
+
+        count > 0
+    }
+
+    // generate the same plays as get_move_seqs(), in the same order, into a
+    // buffer that can be reused between calls
+    pub fn generate_plays(
+        &self,
+        dice_roll: DiceRoll,
+        plays: &mut Vec<PlayBuf>,
+    ) {
+        plays.clear();
+
+        let mut board = self.clone();
+        let mut cur_play = PlayBuf::new();
+
+        if dice_roll.0 == dice_roll.1 {
+            board.search_plays(&[dice_roll.0; 4], &mut cur_play, plays);
+        } else {
+            board.search_plays(
+                &[dice_roll.0, dice_roll.1],
+                &mut cur_play,
+                plays,
+            );
+            let split = plays.len();
+            board.search_plays(
+                &[dice_roll.1, dice_roll.0],
+                &mut cur_play,
+                plays,
+            );
+
+            // if only one of the dice can be used, prefer the larger one. all
+            // the plays have a single move in this case, and both dice have
+            // possible moves iff both permutations generated something.
+            let max_len = plays.iter().map(|p| p.len()).max().unwrap_or(0);
+            if max_len == 1 && split > 0 && split < plays.len() {
+                if dice_roll.0 > dice_roll.1 {
+                    plays.truncate(split);
+                } else {
+                    plays.drain(..split);
+                }
+                return;
+            }
+        }
+
+        // else we must perform maximum number of moves
+        let max_len = plays.iter().map(|p| p.len()).max().unwrap_or(0);
+        plays.retain(|p| p.len() == max_len);
+    }
+}
@@ -0,0 +1,224 @@
+use super::{
+    BoardState, DiceRoll, Location, Move, MoveError, Play, PlayError,
+    MAX_PLAY_LEN,
+};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NotationError {
+    // index of a part of the play that couldn't be parsed, e.g. "13-7"
+    InvalidSyntax(usize),
+    IllegalPlay(PlayError),
+    // the play could be read as more than one of the legal plays
+    AmbiguousPlay,
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotationError::InvalidSyntax(index) => {
+                write!(f, "can't parse move {}", index + 1)
+            }
+            NotationError::IllegalPlay(err) => write!(f, "{}", err),
+            NotationError::AmbiguousPlay => {
+                write!(f, "ambiguous play, write out where each checker stops")
+            }
+        }
+    }
+}
+
+impl Error for NotationError {}
+
+impl From<PlayError> for NotationError {
+    fn from(err: PlayError) -> Self {
+        NotationError::IllegalPlay(err)
+    }
+}
+
+// a single move, without knowing whether it hits, e.g. "13/7"
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.0, self.1)
+    }
+}
+
+// the locations a checker passed through during a play, and whether it hit
+// a blot at each of them
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Path {
+    locations: Vec<Location>,
+    hits: Vec<bool>,
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let last = self.locations.len() - 1;
+        write!(f, "{}", self.locations[0])?;
+        for i in 1..=last {
+            // only show where the checker stopped on the way if it hit there
+            if i == last || self.hits[i] {
+                write!(f, "/{}", self.locations[i])?;
+                if self.hits[i] {
+                    write!(f, "*")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_location(s: &str) -> Option<Location> {
+    match s.to_lowercase().as_str() {
+        "bar" => Some(Location::Bar),
+        "off" => Some(Location::Off),
+        // some programs write the bar as 25 and off as 0
+        s => s.parse().ok().and_then(Location::from_pip),
+    }
+}
+
+// parse a part of a play, e.g. "24/18*/13" or "8/5(2)", into single moves
+fn parse_part(part: &str) -> Option<Vec<Move>> {
+    let (path, count) = match part.find('(') {
+        Some(start) if part.ends_with(')') => {
+            let count = part[start + 1..part.len() - 1].parse().ok()?;
+            (&part[..start], count)
+        }
+        Some(_) => return None,
+        None => (part, 1),
+    };
+
+    let locations = path
+        .split('/')
+        .map(|s| parse_location(s.trim_end_matches('*')))
+        .collect::<Option<Vec<Location>>>()?;
+
+    // no play has more moves than that, and a huge count would take forever
+    // to expand
+    if locations.len() < 2 || count == 0 || count > MAX_PLAY_LEN {
+        return None;
+    }
+
+    let moves: Vec<Move> = locations
+        .windows(2)
+        .map(|pair| Move(pair[0], pair[1]))
+        .collect();
+
+    Some((0..count).flat_map(|_| moves.iter().cloned()).collect())
+}
+
+impl BoardState {
+    // standard notation for a play by the current player, e.g. "24/18 13/11*".
+    // moves by the same checker are joined, and repeated moves are grouped as
+    // in "8/5(2)". returns an empty string for an empty play, and an error if
+    // one of the moves can't be made.
+    pub fn format_play(&self, move_seq: &[Move]) -> Result<String, MoveError> {
+        let player = self.cur_player;
+        let mut board = self.clone();
+        let mut paths: Vec<Path> = vec![];
+
+        for &move_ in move_seq {
+            let opponent_bar =
+                board.checkers_at(player.inverse(), Location::Bar);
+            board.try_apply_move(move_)?;
+            let hit = board.checkers_at(player.inverse(), Location::Bar)
+                > opponent_bar;
+
+            let Move(from, to) = move_;
+            match paths.iter_mut().find(|p| p.locations.last() == Some(&from)) {
+                Some(path) => {
+                    path.locations.push(to);
+                    path.hits.push(hit);
+                }
+                None => paths.push(Path {
+                    locations: vec![from, to],
+                    hits: vec![false, hit],
+                }),
+            }
+        }
+
+        // farthest checkers first
+        paths.sort();
+
+        let mut parts: Vec<String> = vec![];
+        let mut i = 0;
+        while i < paths.len() {
+            let count =
+                paths[i..].iter().take_while(|&p| *p == paths[i]).count();
+            parts.push(if count > 1 {
+                format!("{}({})", paths[i], count)
+            } else {
+                paths[i].to_string()
+            });
+
+            i += count;
+        }
+
+        Ok(parts.join(" "))
+    }
+
+    // parse a play by the current player in standard notation. moves that
+    // cover more than one die, like "24/13", are split up into single moves
+    // according to the dice. hits don't have to be marked, but if a checker
+    // could take different routes that hit different blots, the number of
+    // hits marked decides between them.
+    pub fn parse_play(
+        &self,
+        dice_roll: DiceRoll,
+        play: &str,
+    ) -> Result<Vec<Move>, NotationError> {
+        let mut moves = vec![];
+        for (i, part) in play.split_whitespace().enumerate() {
+            moves.extend(
+                parse_part(part).ok_or(NotationError::InvalidSyntax(i))?,
+            );
+        }
+
+        let err = match self.is_legal_play(dice_roll, &moves) {
+            Ok(()) => return Ok(moves),
+            Err(err) => err,
+        };
+
+        // moves in the notation don't have to match the dice one-to-one, so
+        // look for legal plays that leave the player's checkers in the same
+        // places. moving the farthest checkers first always works if the
+        // moves are possible.
+        let mut sorted_moves = moves.clone();
+        sorted_moves.sort();
+
+        let mut result = self.clone();
+        if result.try_apply_move_seq(sorted_moves.iter()).is_err() {
+            return Err(err.into());
+        }
+
+        let player = self.cur_player;
+        let opponent = player.inverse();
+        let counts = result.to_compact().counts(player);
+
+        let mut candidates: Vec<Play> = self
+            .get_distinct_plays(dice_roll)
+            .into_iter()
+            .filter(|play| play.result.to_compact().counts(player) == counts)
+            .collect();
+
+        if candidates.len() > 1 {
+            let hits = play.matches('*').count();
+            let opponent_bar = self.checkers_at(opponent, Location::Bar);
+            candidates.retain(|play| {
+                play.result.checkers_at(opponent, Location::Bar)
+                    == opponent_bar + hits
+            });
+
+            if candidates.len() != 1 {
+                return Err(NotationError::AmbiguousPlay);
+            }
+        }
+
+        match candidates.pop() {
+            Some(play) => Ok(play.moves),
+            None => Err(err.into()),
+        }
+    }
+}
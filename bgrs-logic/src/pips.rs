@@ -0,0 +1,336 @@
+use super::{BoardState, Location, PlayerColor, PointIndex};
+use std::collections::HashMap;
+use std::fmt;
+
+// average number of pips moved per roll, counting doubles twice
+const PIPS_PER_ROLL: f64 = 49.0 / 6.0;
+
+// what a race formula says about the cube, for the player on roll and their
+// opponent
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RaceAdvice {
+    // whether to double when the cube is centered
+    pub double: bool,
+    // whether to redouble when owning the cube
+    pub redouble: bool,
+    // whether the opponent should take a double
+    pub take: bool,
+}
+
+impl fmt::Display for RaceAdvice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.double, self.take) {
+            (false, _) => write!(f, "no double"),
+            (true, true) => write!(f, "double, take"),
+            (true, false) => write!(f, "double, pass"),
+        }
+    }
+}
+
+// checker counts on points 1-6 of a home board
+type HomeBoard = [u8; 6];
+
+// entries per home board in the bear-off table: one for the expected number
+// of rolls at the start of a turn, and one for each die and number of moves
+// left to make with it
+const SLOTS_PER_HOME_BOARD: usize = 1 + 6 * 4;
+
+// expected numbers of rolls to bear off, for effective_pip_count(). only the
+// home boards that calls actually reach are worked out, and keeping a table
+// around between calls saves working them out again, as the same positions
+// come up again and again. see bearoff_rolls().
+#[derive(Clone, Debug, Default)]
+pub struct BearoffTable {
+    rolls: HashMap<usize, f64>,
+}
+
+impl BearoffTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// binomial coefficients up to 21 choose 6, for home_board_index()
+const BINOMIALS: [[usize; 7]; 22] = binomials();
+
+const fn binomials() -> [[usize; 7]; 22] {
+    let mut table = [[0; 7]; 22];
+    let mut n = 0;
+    while n < 22 {
+        table[n][0] = 1;
+        let mut k = 1;
+        while k < 7 && n > 0 {
+            table[n][k] = table[n - 1][k - 1] + table[n - 1][k];
+            k += 1;
+        }
+        n += 1;
+    }
+
+    table
+}
+
+// a unique number for each home board with up to 15 checkers. the
+// counts and the checkers that are off are split by 6 separators among 21
+// places, and the separators' places are ranked in the combinatorial number
+// system.
+fn home_board_index(home: HomeBoard) -> usize {
+    let mut checkers = 0;
+    let mut index = 0;
+    for (i, &count) in home.iter().enumerate() {
+        checkers += count as usize;
+        index += BINOMIALS[checkers + i][i + 1];
+    }
+
+    index
+}
+
+// home boards that can result from using one die
+fn bearoff_moves(
+    home: HomeBoard,
+    die: usize,
+) -> impl Iterator<Item = HomeBoard> {
+    let highest = home
+        .iter()
+        .rposition(|&count| count > 0)
+        .map_or(0, |i| i + 1);
+
+    (1..=highest).filter_map(move |point| {
+        // checkers may be borne off with a higher die only from the highest
+        // point
+        if home[point - 1] == 0 || (point < die && point != highest) {
+            return None;
+        }
+
+        let mut result = home;
+        result[point - 1] -= 1;
+        if point > die {
+            result[point - die - 1] += 1;
+        }
+
+        Some(result)
+    })
+}
+
+// expected number of rolls to bear off all of the checkers in a home board,
+// playing to bear off as quickly as possible, after first moving the given
+// number of checkers with die. in a bear-off every die can always be used,
+// so plays always use all of the dice.
+fn bearoff_rolls(
+    home: HomeBoard,
+    die: usize,
+    moves_left: usize,
+    table: &mut BearoffTable,
+) -> f64 {
+    if home == [0; 6] {
+        return 0.0;
+    }
+
+    let slot = if moves_left > 0 {
+        (die - 1) * 4 + moves_left
+    } else {
+        0
+    };
+    let index = home_board_index(home) * SLOTS_PER_HOME_BOARD + slot;
+    if let Some(&rolls) = table.rolls.get(&index) {
+        return rolls;
+    }
+
+    let rolls = if moves_left > 0 {
+        let mut best = f64::INFINITY;
+        for result in bearoff_moves(home, die) {
+            best = best.min(bearoff_rolls(result, die, moves_left - 1, table));
+        }
+
+        best
+    } else {
+        let mut total = 0.0;
+        for die_1 in 1..=6 {
+            total += bearoff_rolls(home, die_1, 4, table);
+
+            for die_2 in die_1 + 1..=6 {
+                // either die may be played first
+                let mut best = f64::INFINITY;
+                for &(first, second) in &[(die_1, die_2), (die_2, die_1)] {
+                    for result in bearoff_moves(home, first) {
+                        best =
+                            best.min(bearoff_rolls(result, second, 1, table));
+                    }
+                }
+
+                total += 2.0 * best;
+            }
+        }
+
+        1.0 + total / 36.0
+    };
+
+    table.rolls.insert(index, rolls);
+    rolls
+}
+
+impl BoardState {
+    fn index_pip(player: PlayerColor, index: PointIndex) -> usize {
+        Location::from_index(player, index).pip()
+    }
+
+    // total number of pips player has to move to bear off all of their
+    // checkers
+    pub fn pip_count(&self, player: PlayerColor) -> usize {
+        self.points
+            .iter()
+            .enumerate()
+            .filter(|(_, point)| point.is_used_by(player))
+            .map(|(index, point)| {
+                point.checker_count * Self::index_pip(player, index)
+            })
+            .sum()
+    }
+
+    // how many pips player is ahead of their opponent, or negative if
+    // they're behind
+    pub fn pip_difference(&self, player: PlayerColor) -> isize {
+        self.pip_count(player.inverse()) as isize
+            - self.pip_count(player) as isize
+    }
+
+    // whether the players' checkers have passed each other, so that neither
+    // can hit the other
+    pub fn is_race(&self) -> bool {
+        let last_black = self
+            .points
+            .iter()
+            .position(|point| point.is_used_by(PlayerColor::Black));
+        let last_white = self
+            .points
+            .iter()
+            .rposition(|point| point.is_used_by(PlayerColor::White));
+
+        match (last_black, last_white) {
+            (Some(black), Some(white)) => black > white,
+            _ => true,
+        }
+    }
+
+    fn home_board(&self, player: PlayerColor) -> HomeBoard {
+        let mut home = [0; 6];
+        for (i, count) in home.iter_mut().enumerate() {
+            *count = self.checkers_at(player, Location::Point(i + 1)) as u8;
+        }
+
+        home
+    }
+
+    // the pip count plus the pips that are wasted on average while bearing
+    // off, i.e. the expected number of rolls to bear off times the average
+    // roll. it's exact once all of player's checkers are in their home
+    // board. before that, checkers outside are counted as if they'd come in
+    // to the 6, 5 and 4 points in turn, which is only a rough estimate.
+    pub fn effective_pip_count(
+        &self,
+        player: PlayerColor,
+        table: &mut BearoffTable,
+    ) -> f64 {
+        let mut home = self.home_board(player);
+
+        let outside = self.checkers_on_board(player)
+            - home.iter().map(|&count| count as usize).sum::<usize>();
+        for i in 0..outside {
+            home[5 - i % 3] += 1;
+        }
+
+        let estimated_pips: usize =
+            (1..=6).map(|n| n * home[n - 1] as usize).sum();
+        let rolls = bearoff_rolls(home, 0, 0, table);
+        let wastage = rolls * PIPS_PER_ROLL - estimated_pips as f64;
+
+        self.pip_count(player) as f64 + wastage
+    }
+
+    fn checkers_on_board(&self, player: PlayerColor) -> usize {
+        self.points
+            .iter()
+            .filter(|point| point.is_used_by(player))
+            .map(|point| point.checker_count)
+            .sum()
+    }
+
+    // Jeff Keith's adjusted pip count: add 2 pips for each checker beyond
+    // one on the 1 point, 1 for each checker beyond one on the 2 point, 1 for
+    // each checker beyond three on the 3 point, and 1 for each empty point
+    // among the 4, 5 and 6 points
+    pub fn keith_count(&self, player: PlayerColor) -> usize {
+        let home = self.home_board(player);
+        let extra =
+            |n: usize, free: u8| home[n - 1].saturating_sub(free) as usize;
+
+        self.pip_count(player)
+            + 2 * extra(1, 1)
+            + extra(2, 1)
+            + extra(3, 3)
+            + home[3..].iter().filter(|&&count| count == 0).count()
+    }
+
+    // the player on roll increases their Keith count by a seventh. they
+    // should double if it's at most 4 more than the opponent's, and redouble
+    // if it's at most 3 more, and the opponent should take if it's at least
+    // 2 more.
+    pub fn keith_advice(&self) -> RaceAdvice {
+        let player = self.cur_player;
+        let count = self.keith_count(player) as f64 * 8.0 / 7.0;
+        let lead = count - self.keith_count(player.inverse()) as f64;
+
+        RaceAdvice {
+            double: lead <= 4.0,
+            redouble: lead <= 3.0,
+            take: lead >= 2.0,
+        }
+    }
+
+    // Edward Thorp's count: the pip count, plus 2 for each checker left, plus
+    // 1 for each checker on the 1 point, minus 1 for each point held in the
+    // home board
+    pub fn thorp_count(&self, player: PlayerColor) -> usize {
+        let home = self.home_board(player);
+
+        self.pip_count(player)
+            + 2 * self.checkers_on_board(player)
+            + home[0] as usize
+            - home.iter().filter(|&&count| count > 0).count()
+    }
+
+    // the player on roll adds a tenth to their Thorp count if it's over 30.
+    // they should double if it's then at most 2 more than the opponent's,
+    // and redouble if it's at most 1 more, and the opponent should take if
+    // it's at least 2 less.
+    pub fn thorp_advice(&self) -> RaceAdvice {
+        let player = self.cur_player;
+        let mut count = self.thorp_count(player) as f64;
+        if count > 30.0 {
+            count *= 1.1;
+        }
+        let lead = count - self.thorp_count(player.inverse()) as f64;
+
+        RaceAdvice {
+            double: lead <= 2.0,
+            redouble: lead <= 1.0,
+            take: lead >= -2.0,
+        }
+    }
+
+    // the 8-9-12 rule for long races: the player on roll should double if
+    // they lead by at least 8% of their pip count, and redouble if they lead
+    // by at least 9%, and the opponent should take if the lead is at most
+    // 12%
+    pub fn rule_8_9_12_advice(&self) -> RaceAdvice {
+        let player = self.cur_player;
+        let lead = self.pip_difference(player) as f64
+            / self.pip_count(player).max(1) as f64;
+
+        RaceAdvice {
+            double: lead >= 0.08,
+            redouble: lead >= 0.09,
+            take: lead <= 0.12,
+        }
+    }
+}
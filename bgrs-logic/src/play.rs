@@ -0,0 +1,46 @@
+use super::{BoardState, DiceRoll, Move};
+use std::collections::HashMap;
+
+// a play, along with the other legal orderings of it that lead to the same
+// position
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Play {
+    // one representative ordering, which moves the farthest checkers first
+    pub moves: Vec<Move>,
+    // all of the orderings, including moves
+    pub equivalents: Vec<Vec<Move>>,
+    // position after making the play, before the turn ends
+    pub result: BoardState,
+}
+
+impl BoardState {
+    // like get_move_seqs(), but returns a single play for each position that
+    // can be reached
+    pub fn get_distinct_plays(&self, dice_roll: DiceRoll) -> Vec<Play> {
+        let mut plays: Vec<Play> = vec![];
+        let mut play_indices: HashMap<BoardState, usize> = HashMap::new();
+
+        for move_seq in self.get_move_seqs(dice_roll) {
+            let result = self.with_move_seq(move_seq.iter());
+
+            if let Some(&index) = play_indices.get(&result) {
+                let play = &mut plays[index];
+                if move_seq < play.moves {
+                    play.moves = move_seq.clone();
+                }
+
+                play.equivalents.push(move_seq);
+            } else {
+                play_indices.insert(result.clone(), plays.len());
+                plays.push(Play {
+                    moves: move_seq.clone(),
+                    equivalents: vec![move_seq],
+                    result,
+                });
+            }
+        }
+
+        plays
+    }
+}
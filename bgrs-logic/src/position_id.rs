@@ -0,0 +1,157 @@
+use super::{BoardState, CompactBoard, PlayerColor, CHECKERS_PER_PLAYER};
+use std::error::Error;
+use std::fmt;
+
+const BASE64_CHARS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// gnubg ids are base64 without the padding
+pub(crate) fn encode_base64(bytes: &[u8]) -> String {
+    let mut ret = String::new();
+    for chunk in bytes.chunks(3) {
+        let mut group = [0u8; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let bits =
+            (group[0] as u32) << 16 | (group[1] as u32) << 8 | group[2] as u32;
+
+        for i in 0..=chunk.len() {
+            let index = (bits >> (18 - 6 * i)) & 0x3f;
+            ret.push(BASE64_CHARS[index as usize] as char);
+        }
+    }
+
+    ret
+}
+
+// decodes unpadded base64. returns the first invalid character on error.
+pub(crate) fn decode_base64(s: &str) -> Result<Vec<u8>, char> {
+    let mut ret = vec![];
+    let mut bits = 0u32;
+    let mut bit_count = 0;
+    for c in s.chars() {
+        let value =
+            BASE64_CHARS.iter().position(|&b| b as char == c).ok_or(c)?;
+
+        bits = bits << 6 | value as u32;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            ret.push((bits >> bit_count) as u8);
+            bits &= (1 << bit_count) - 1;
+        }
+    }
+
+    Ok(ret)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PositionIdError {
+    InvalidLength,
+    InvalidCharacter(char),
+    // more than 15 checkers for one player, or more than 80 bits in total
+    TooManyCheckers,
+    // both players have checkers on the same point
+    SharedPoint,
+}
+
+impl fmt::Display for PositionIdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PositionIdError::InvalidLength => {
+                write!(f, "position id must be 14 characters long")
+            }
+            PositionIdError::InvalidCharacter(c) => {
+                write!(f, "invalid character '{}' in position id", c)
+            }
+            PositionIdError::TooManyCheckers => write!(f, "too many checkers"),
+            PositionIdError::SharedPoint => {
+                write!(f, "both players have checkers on the same point")
+            }
+        }
+    }
+}
+
+impl Error for PositionIdError {}
+
+// gnubg position ids encode 80 bits: for the player who isn't on roll and then
+// for the player on roll, each of points 1-24 from that player's point of view
+// and then the bar, as a 1 bit per checker followed by a 0. bits are filled in
+// from the lowest bit of each byte.
+const POSITION_ID_BYTES: usize = 10;
+const POSITION_ID_LEN: usize = 14;
+
+impl BoardState {
+    // gnubg's 14 character position id, from the point of view of the current
+    // player
+    pub fn to_position_id(&self) -> String {
+        let compact = self.to_compact();
+        let mut bytes = [0u8; POSITION_ID_BYTES];
+        let mut bit = 0;
+
+        for &player in [self.cur_player.inverse(), self.cur_player].iter() {
+            for &count in compact.counts(player).iter() {
+                for _ in 0..count {
+                    bytes[bit / 8] |= 1 << (bit % 8);
+                    bit += 1;
+                }
+                // terminating 0 bit
+                bit += 1;
+            }
+        }
+
+        encode_base64(&bytes)
+    }
+
+    // a position id doesn't say who the players are, so the caller must say
+    // which color is on roll
+    pub fn from_position_id(
+        id: &str,
+        cur_player: PlayerColor,
+    ) -> Result<Self, PositionIdError> {
+        if id.chars().count() != POSITION_ID_LEN {
+            return Err(PositionIdError::InvalidLength);
+        }
+
+        let bytes =
+            decode_base64(id).map_err(PositionIdError::InvalidCharacter)?;
+        let mut bits = (0..POSITION_ID_BYTES * 8)
+            .map(|bit| bytes[bit / 8] & (1 << (bit % 8)) != 0);
+
+        // counts for the player not on roll, then for the player on roll
+        let mut counts = [[0u8; 25]; 2];
+        for player_counts in counts.iter_mut() {
+            for count in player_counts.iter_mut() {
+                loop {
+                    match bits.next() {
+                        Some(true) => *count += 1,
+                        Some(false) => break,
+                        None => return Err(PositionIdError::TooManyCheckers),
+                    }
+                }
+            }
+
+            let total: usize = player_counts.iter().map(|&c| c as usize).sum();
+            if total > CHECKERS_PER_PLAYER {
+                return Err(PositionIdError::TooManyCheckers);
+            }
+        }
+
+        // point n for one player is point 25 - n for the other
+        let [opponent, player] = counts;
+        if (0..24).any(|i| player[i] > 0 && opponent[23 - i] > 0) {
+            return Err(PositionIdError::SharedPoint);
+        }
+
+        let compact = match cur_player {
+            PlayerColor::Black => {
+                CompactBoard::from_counts(&player, &opponent, cur_player)
+            }
+            PlayerColor::White => {
+                CompactBoard::from_counts(&opponent, &player, cur_player)
+            }
+        };
+
+        Ok(compact.to_board())
+    }
+}
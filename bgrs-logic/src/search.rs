@@ -0,0 +1,218 @@
+use super::{BoardState, DiceRoll, Evaluator, Play, Probabilities};
+
+// which candidate plays to look at more deeply, after ranking them all by
+// the evaluator alone, like gnubg's move filters: the best `accept` plays
+// are always kept, and then up to `extra` more whose equity is within
+// `threshold` of the best one's. the best play is kept even if accept is 0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MoveFilter {
+    pub accept: usize,
+    pub extra: usize,
+    pub threshold: f64,
+}
+
+impl MoveFilter {
+    // plays must be sorted from best to worst
+    fn apply(&self, plays: &mut Vec<(Play, Probabilities)>) {
+        let best = match plays.first() {
+            Some((_, probs)) => probs.equity(),
+            None => return,
+        };
+
+        let accept = self.accept.max(1).min(plays.len());
+        let extra = plays[accept..]
+            .iter()
+            .take(self.extra)
+            .take_while(|(_, probs)| best - probs.equity() <= self.threshold)
+            .count();
+
+        plays.truncate(accept + extra);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchSettings {
+    // how many plays ahead to look, counting the one being chosen. 1 picks
+    // the play that the evaluator likes best, and each ply after that
+    // averages over the next player's rolls.
+    pub plies: usize,
+    // filter for the plays at each level of the search, starting with the
+    // one being chosen. the last filter is used for the levels after that,
+    // and every play is kept if there are no filters. plays aren't filtered
+    // at the last level, as they're only evaluated there anyway.
+    pub filters: Vec<MoveFilter>,
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        Self {
+            plies: 2,
+            filters: vec![MoveFilter {
+                accept: 0,
+                extra: 8,
+                threshold: 0.16,
+            }],
+        }
+    }
+}
+
+// the 21 distinct rolls, with how many of the 36 ways the dice can land give
+// each one
+fn distinct_rolls() -> impl Iterator<Item = (DiceRoll, f64)> {
+    (1..=6).flat_map(|die_1| {
+        (die_1..=6).map(move |die_2| {
+            let ways = if die_1 == die_2 { 1.0 } else { 2.0 };
+            ((die_1, die_2), ways)
+        })
+    })
+}
+
+// total += probs * weight
+fn add_weighted(total: &mut Probabilities, probs: &Probabilities, weight: f64) {
+    total.win += probs.win * weight;
+    total.win_gammon += probs.win_gammon * weight;
+    total.win_backgammon += probs.win_backgammon * weight;
+    total.lose_gammon += probs.lose_gammon * weight;
+    total.lose_backgammon += probs.lose_backgammon * weight;
+}
+
+// an expectiminimax search that chooses plays by looking ahead at every roll
+// that could come next. it always visits rolls and plays in the same order
+// and breaks ties between plays the same way, so the same settings always
+// choose the same plays.
+pub struct Search {
+    pub evaluator: Box<dyn Evaluator>,
+    pub settings: SearchSettings,
+}
+
+impl Search {
+    pub fn new(
+        evaluator: Box<dyn Evaluator>,
+        settings: SearchSettings,
+    ) -> Self {
+        Self {
+            evaluator,
+            settings,
+        }
+    }
+
+    // the best play for the player on roll, or None if there's no legal
+    // play
+    pub fn best_play(
+        &self,
+        board: &BoardState,
+        dice_roll: DiceRoll,
+    ) -> Option<Play> {
+        self.choose(board, dice_roll, self.settings.plies.max(1), 0)
+            .map(|(play, _)| play)
+    }
+
+    // chances for the player on roll, before they roll, looking ahead the
+    // given number of plies. with 0 plies it's just the evaluator's opinion.
+    pub fn evaluate_position(
+        &self,
+        board: &BoardState,
+        plies: usize,
+    ) -> Probabilities {
+        self.position_value(board, plies, 0)
+    }
+
+    fn filter(&self, level: usize) -> Option<&MoveFilter> {
+        let filters = &self.settings.filters;
+        filters.get(level).or_else(|| filters.last())
+    }
+
+    fn position_value(
+        &self,
+        board: &BoardState,
+        plies: usize,
+        level: usize,
+    ) -> Probabilities {
+        if plies == 0 || board.get_result().is_some() {
+            return self.evaluator.evaluate(board);
+        }
+
+        let mut total = Probabilities {
+            win: 0.0,
+            win_gammon: 0.0,
+            win_backgammon: 0.0,
+            lose_gammon: 0.0,
+            lose_backgammon: 0.0,
+        };
+
+        for (dice_roll, ways) in distinct_rolls() {
+            let probs = match self.choose(board, dice_roll, plies, level) {
+                Some((_, probs)) => probs,
+                // the player can't move, so the turn just passes
+                None => self.value_after_play(board, plies - 1, level + 1),
+            };
+
+            add_weighted(&mut total, &probs, ways / 36.0);
+        }
+
+        total
+    }
+
+    // chances for the player who just made a play that led to result
+    fn value_after_play(
+        &self,
+        result: &BoardState,
+        plies: usize,
+        level: usize,
+    ) -> Probabilities {
+        let mut board = result.clone();
+        board.end_turn();
+        self.position_value(&board, plies, level).invert()
+    }
+
+    fn choose(
+        &self,
+        board: &BoardState,
+        dice_roll: DiceRoll,
+        plies: usize,
+        level: usize,
+    ) -> Option<(Play, Probabilities)> {
+        // rank every play by the evaluator first, to see which ones are
+        // worth searching
+        let mut plays = board
+            .get_distinct_plays(dice_roll)
+            .into_iter()
+            .map(|play| {
+                let probs = self.value_after_play(&play.result, 0, level + 1);
+                (play, probs)
+            })
+            .collect::<Vec<_>>();
+
+        if plies > 1 {
+            // a stable sort, so that equal plays stay in the order they were
+            // generated
+            plays.sort_by(|(_, a), (_, b)| b.equity().total_cmp(&a.equity()));
+
+            if let Some(filter) = self.filter(level) {
+                filter.apply(&mut plays);
+            }
+
+            for (play, probs) in plays.iter_mut() {
+                *probs =
+                    self.value_after_play(&play.result, plies - 1, level + 1);
+            }
+        }
+
+        let mut best: Option<(Play, Probabilities)> = None;
+        for (play, probs) in plays {
+            let better = match best {
+                Some((_, ref best_probs)) => {
+                    probs.equity() > best_probs.equity()
+                }
+                None => true,
+            };
+            if better {
+                best = Some((play, probs));
+            }
+        }
+
+        best
+    }
+}
@@ -0,0 +1,258 @@
+// serde support for types whose fields can't be trusted as they are after
+// deserializing. everything else derives Serialize and Deserialize directly.
+use super::{
+    Action, BoardState, CompactBoard, CubeState, Game, Location, Move, Phase,
+    PlayBuf, PlayerColor, PointState, Setup, CHECKERS_PER_PLAYER, MAX_PLAY_LEN,
+};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::iter;
+
+// same fields as BoardState, before they've been checked
+#[derive(Deserialize)]
+struct BoardStateData {
+    points: [PointState; 26],
+    cur_player: PlayerColor,
+    black_borne_off: usize,
+    white_borne_off: usize,
+}
+
+impl<'de> Deserialize<'de> for BoardState {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let data = BoardStateData::deserialize(deserializer)?;
+        let board = BoardState {
+            points: data.points,
+            cur_player: data.cur_player,
+            black_borne_off: data.black_borne_off,
+            white_borne_off: data.white_borne_off,
+        };
+
+        // each bar always has its owner's color, even when it's empty
+        if board.points[0].checker_color != PlayerColor::Black
+            || board.points[25].checker_color != PlayerColor::White
+        {
+            return Err(D::Error::custom("bar has the wrong color"));
+        }
+
+        for &player in &[PlayerColor::Black, PlayerColor::White] {
+            // the counts can be anything, so they're added up without
+            // overflowing
+            let total = board
+                .points
+                .iter()
+                .filter(|point| point.is_used_by(player))
+                .map(|point| point.checker_count)
+                .chain(iter::once(board.borne_off(player)))
+                .try_fold(0usize, |total, count| total.checked_add(count));
+
+            if total != Some(CHECKERS_PER_PLAYER) {
+                return Err(D::Error::custom(format!(
+                    "{} doesn't have {} checkers",
+                    player, CHECKERS_PER_PLAYER
+                )));
+            }
+        }
+
+        Ok(board)
+    }
+}
+
+// plays are written as a plain list of moves
+impl Serialize for PlayBuf {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for PlayBuf {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let moves = Vec::<Move>::deserialize(deserializer)?;
+        if moves.len() > MAX_PLAY_LEN {
+            return Err(D::Error::invalid_length(
+                moves.len(),
+                &"at most 4 moves",
+            ));
+        }
+
+        let mut play = PlayBuf::new();
+        for move_ in moves {
+            play.push(move_);
+        }
+
+        Ok(play)
+    }
+}
+
+// compact boards are written out in full, and checked in the same way
+impl Serialize for CompactBoard {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.to_board().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CompactBoard {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        Ok(BoardState::deserialize(deserializer)?.to_compact())
+    }
+}
+
+// same variants as Location, before the point has been checked
+#[derive(Deserialize)]
+enum LocationData {
+    Bar,
+    Point(usize),
+    Off,
+}
+
+impl<'de> Deserialize<'de> for Location {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        match LocationData::deserialize(deserializer)? {
+            LocationData::Bar => Ok(Location::Bar),
+            LocationData::Point(n) if (1..=24).contains(&n) => {
+                Ok(Location::Point(n))
+            }
+            LocationData::Point(n) => {
+                Err(D::Error::custom(format!("there's no point {}", n)))
+            }
+            LocationData::Off => Ok(Location::Off),
+        }
+    }
+}
+
+// same fields as Game, before they've been checked
+#[derive(Deserialize)]
+struct GameData {
+    board: BoardState,
+    cube: CubeState,
+    phase: Phase,
+    auto_doubles_left: usize,
+    history: Vec<(PlayerColor, Action)>,
+    dice_seed: Option<u64>,
+    setup: Option<Setup>,
+}
+
+impl GameData {
+    // the ways the game could have started. games that weren't set up
+    // always start with the cube at 1, so its value now says how many times
+    // it was doubled automatically after tied opening rolls.
+    fn starts(&self) -> Vec<Game> {
+        if let Some(setup) = &self.setup {
+            return vec![Game::from_setup(setup.clone())];
+        }
+
+        let mut doubles = 0;
+        for (_, action) in &self.history {
+            match action {
+                Action::Take => doubles += 1,
+                Action::Beaver => doubles += 2,
+                _ => {}
+            }
+        }
+
+        let value = self.cube.value;
+        if !value.is_power_of_two() || value.trailing_zeros() < doubles {
+            return vec![];
+        }
+        let auto_doubles = (value.trailing_zeros() - doubles) as usize;
+
+        let cube = CubeState {
+            value: 1,
+            owner: None,
+            offered_by: None,
+            ..self.cube
+        };
+        let opening_roll = match self.history.first() {
+            Some((_, action)) => matches!(action, Action::OpeningRoll(..)),
+            None => self.phase == Phase::OpeningRoll,
+        };
+
+        if opening_roll {
+            let max_auto_doubles = self.auto_doubles_left + auto_doubles;
+            vec![Game::new_with_opening_roll(cube, max_auto_doubles)]
+        } else if auto_doubles == 0 && self.auto_doubles_left == 0 {
+            // whoever rolled first may have been either player
+            vec![
+                Game::new(PlayerColor::Black, cube),
+                Game::new(PlayerColor::White, cube),
+            ]
+        } else {
+            vec![]
+        }
+    }
+
+    // whether a setup describes a position the game could be in
+    fn setup_is_valid(setup: &Setup) -> bool {
+        let cube = &setup.cube;
+        let offered = match setup.phase {
+            Phase::CubeDecision => {
+                cube.offered_by == Some(setup.board.cur_player)
+            }
+            _ => cube.offered_by.is_none(),
+        };
+        let dice = match setup.phase {
+            Phase::AwaitingMove((die_1, die_2)) => {
+                (1..=6).contains(&die_1) && (1..=6).contains(&die_2)
+            }
+            _ => true,
+        };
+
+        offered && dice && cube.value.is_power_of_two()
+    }
+
+    fn matches(&self, game: &Game) -> bool {
+        game.board() == &self.board
+            && game.cube() == &self.cube
+            && game.phase() == self.phase
+            && game.auto_doubles_left() == self.auto_doubles_left
+            && game.history() == self.history.as_slice()
+            && game.setup() == self.setup.as_ref()
+    }
+}
+
+// games are checked by replaying their history, from the setup if there is
+// one, and making sure that it ends up in the same state
+impl<'de> Deserialize<'de> for Game {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let data = GameData::deserialize(deserializer)?;
+        if let Some(setup) = &data.setup {
+            if !GameData::setup_is_valid(setup) {
+                return Err(D::Error::custom("invalid setup"));
+            }
+        }
+
+        let replay = |mut game: Game| {
+            for (player, action) in &data.history {
+                game.apply(*player, action.clone()).ok()?;
+            }
+            Some(game)
+        };
+
+        let mut game = data
+            .starts()
+            .into_iter()
+            .filter_map(replay)
+            .find(|game| data.matches(game))
+            .ok_or_else(|| {
+                D::Error::custom("game doesn't match its history")
+            })?;
+        game.set_dice_seed(data.dice_seed);
+
+        Ok(game)
+    }
+}
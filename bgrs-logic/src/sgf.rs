@@ -0,0 +1,899 @@
+use super::{
+    Action, CompactBoard, CubeState, DiceRoll, Game, GameError, Location,
+    Match, MatchRecord, Move, Phase, PlayerColor, Setup, WinKind,
+    CHECKERS_PER_PLAYER,
+};
+use std::error::Error;
+use std::fmt;
+
+// errors only implement Serialize, as InvalidValue holds a static string
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SgfErrorKind {
+    // the text isn't valid SGF, e.g. a value is missing its closing bracket
+    Syntax,
+    // a game tree that isn't backgammon, i.e. doesn't have GM[6]
+    NotBackgammon,
+    // a property value that can't be understood, e.g. B[72]
+    InvalidValue(&'static str),
+    // AE, AB or AW describe an impossible position
+    InvalidPosition,
+    // setup properties after the first move of a game
+    SetupAfterStart,
+    // a game after one of the players already won the match
+    MatchOver,
+    // e.g. an illegal play, or a take without a double
+    InvalidAction(GameError),
+    // variations nested more than MAX_DEPTH deep
+    TooDeep,
+    // the result in RE doesn't match the game
+    WrongResult,
+    // the score in MI doesn't match the games before
+    WrongScore,
+}
+
+impl fmt::Display for SgfErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SgfErrorKind::Syntax => write!(f, "invalid SGF"),
+            SgfErrorKind::NotBackgammon => write!(f, "not a backgammon game"),
+            SgfErrorKind::InvalidValue(property) => {
+                write!(f, "invalid value for {}", property)
+            }
+            SgfErrorKind::InvalidPosition => write!(f, "invalid position"),
+            SgfErrorKind::SetupAfterStart => {
+                write!(f, "position set up after the game started")
+            }
+            SgfErrorKind::MatchOver => write!(f, "match is already over"),
+            SgfErrorKind::InvalidAction(err) => write!(f, "{}", err),
+            SgfErrorKind::TooDeep => write!(f, "variations nested too deeply"),
+            SgfErrorKind::WrongResult => {
+                write!(f, "result doesn't match the game")
+            }
+            SgfErrorKind::WrongScore => {
+                write!(f, "score doesn't match the games before")
+            }
+        }
+    }
+}
+
+impl From<GameError> for SgfErrorKind {
+    fn from(err: GameError) -> Self {
+        SgfErrorKind::InvalidAction(err)
+    }
+}
+
+// a problem in an SGF file, with the game tree and the node in it where it
+// was found, both starting from 1
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SgfError {
+    pub game: usize,
+    pub node: usize,
+    pub kind: SgfErrorKind,
+}
+
+impl fmt::Display for SgfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "game {}, node {}: {}", self.game, self.node, self.kind)
+    }
+}
+
+impl Error for SgfError {}
+
+// a property and its values, e.g. AB[a][a][f]. values are stored without
+// SGF's escaping.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SgfProperty {
+    pub id: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SgfNode {
+    pub properties: Vec<SgfProperty>,
+}
+
+impl SgfNode {
+    pub fn get(&self, id: &str) -> Option<&[String]> {
+        self.properties
+            .iter()
+            .find(|property| property.id == id)
+            .map(|property| property.values.as_slice())
+    }
+
+    // first value of a property
+    pub fn value(&self, id: &str) -> Option<&str> {
+        self.get(id)
+            .and_then(|values| values.first())
+            .map(|value| value.as_str())
+    }
+
+    // replace a property's values, or add it if it isn't there yet
+    pub fn set(&mut self, id: &str, values: Vec<String>) {
+        match self
+            .properties
+            .iter_mut()
+            .find(|property| property.id == id)
+        {
+            Some(property) => property.values = values,
+            None => self.properties.push(SgfProperty {
+                id: id.to_string(),
+                values,
+            }),
+        }
+    }
+
+    fn push(&mut self, id: &str, value: String) {
+        self.set(id, vec![value]);
+    }
+}
+
+// a sequence of nodes, followed by any variations branching off after them
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SgfTree {
+    pub nodes: Vec<SgfNode>,
+    pub variations: Vec<SgfTree>,
+}
+
+impl SgfTree {
+    // the nodes of the game as it was played, following the first variation
+    // wherever there's a choice
+    pub fn main_line(&self) -> Vec<&SgfNode> {
+        let mut nodes: Vec<&SgfNode> = self.nodes.iter().collect();
+        if let Some(variation) = self.variations.first() {
+            nodes.extend(variation.main_line());
+        }
+
+        nodes
+    }
+
+    fn write(&self, out: &mut String) {
+        out.push('(');
+        for (i, node) in self.nodes.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+
+            out.push(';');
+            for property in &node.properties {
+                out.push_str(&property.id);
+                for value in &property.values {
+                    out.push('[');
+                    for c in value.chars() {
+                        if c == ']' || c == '\\' {
+                            out.push('\\');
+                        }
+                        out.push(c);
+                    }
+                    out.push(']');
+                }
+            }
+        }
+
+        for variation in &self.variations {
+            out.push('\n');
+            variation.write(out);
+        }
+
+        out.push(')');
+    }
+}
+
+// how deeply variations may be nested. trees are parsed, written and dropped
+// recursively, so a hostile file could otherwise overflow the stack.
+const MAX_DEPTH: usize = 1000;
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    // number of trees we're inside of
+    depth: usize,
+    // where we are, for errors
+    game: usize,
+    node: usize,
+}
+
+impl Parser {
+    fn error(&self) -> SgfError {
+        SgfError {
+            game: self.game,
+            node: self.node,
+            kind: SgfErrorKind::Syntax,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        while self.pos < self.chars.len()
+            && self.chars[self.pos].is_whitespace()
+        {
+            self.pos += 1;
+        }
+
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), SgfError> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn tree(&mut self) -> Result<SgfTree, SgfError> {
+        self.expect('(')?;
+
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(SgfError {
+                kind: SgfErrorKind::TooDeep,
+                ..self.error()
+            });
+        }
+
+        let mut tree = SgfTree::default();
+        while self.peek() == Some(';') {
+            self.pos += 1;
+            self.node += 1;
+            tree.nodes.push(self.node()?);
+        }
+
+        if tree.nodes.is_empty() {
+            return Err(self.error());
+        }
+
+        while self.peek() == Some('(') {
+            tree.variations.push(self.tree()?);
+        }
+
+        self.expect(')')?;
+        self.depth -= 1;
+        Ok(tree)
+    }
+
+    fn node(&mut self) -> Result<SgfNode, SgfError> {
+        let mut node = SgfNode::default();
+
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            let start = self.pos;
+            while self
+                .chars
+                .get(self.pos)
+                .is_some_and(|c| c.is_ascii_alphabetic())
+            {
+                self.pos += 1;
+            }
+            let id: String = self.chars[start..self.pos].iter().collect();
+
+            let mut values = vec![];
+            while self.peek() == Some('[') {
+                self.pos += 1;
+                values.push(self.value()?);
+            }
+
+            if values.is_empty() {
+                return Err(self.error());
+            }
+
+            node.properties.push(SgfProperty { id, values });
+        }
+
+        Ok(node)
+    }
+
+    // the rest of a value after its opening bracket
+    fn value(&mut self) -> Result<String, SgfError> {
+        let mut value = String::new();
+
+        loop {
+            let c = *self.chars.get(self.pos).ok_or(self.error())?;
+            self.pos += 1;
+
+            match c {
+                ']' => return Ok(value),
+                '\\' => {
+                    let escaped =
+                        *self.chars.get(self.pos).ok_or(self.error())?;
+                    self.pos += 1;
+                    // a backslash before a line break just joins the lines
+                    if escaped == '\r'
+                        && self.chars.get(self.pos) == Some(&'\n')
+                    {
+                        self.pos += 1;
+                    } else if escaped != '\n' && escaped != '\r' {
+                        value.push(escaped);
+                    }
+                }
+                c => value.push(c),
+            }
+        }
+    }
+}
+
+fn color_letter(player: PlayerColor) -> &'static str {
+    match player {
+        PlayerColor::Black => "B",
+        PlayerColor::White => "W",
+    }
+}
+
+// points are written as letters from black's point of view for both players:
+// black's point 1 is 'a' and white's point 1 is 'x'. the bar is 'y' and off
+// is 'z'.
+fn location_letter(player: PlayerColor, location: Location) -> char {
+    let index = match (location, player) {
+        (Location::Bar, _) => 24,
+        (Location::Off, _) => 25,
+        (Location::Point(n), PlayerColor::Black) => n - 1,
+        (Location::Point(n), PlayerColor::White) => 24 - n,
+    };
+
+    (b'a' + index as u8) as char
+}
+
+fn letter_location(player: PlayerColor, letter: char) -> Option<Location> {
+    match letter {
+        'a'..='x' => {
+            let index = letter as usize - 'a' as usize;
+            Some(Location::Point(match player {
+                PlayerColor::Black => index + 1,
+                PlayerColor::White => 24 - index,
+            }))
+        }
+        'y' => Some(Location::Bar),
+        'z' => Some(Location::Off),
+        _ => None,
+    }
+}
+
+fn parse_dice(s: &str) -> Option<DiceRoll> {
+    let digits: Vec<usize> = s
+        .chars()
+        .map(|c| c.to_digit(10).map_or(0, |d| d as usize))
+        .collect();
+
+    match *digits.as_slice() {
+        [die_1, die_2]
+            if (1..=6).contains(&die_1) && (1..=6).contains(&die_2) =>
+        {
+            Some((die_1, die_2))
+        }
+        _ => None,
+    }
+}
+
+// a move node's value, e.g. "52mhmj" for 13/8 13/11 by black
+fn format_move(
+    player: PlayerColor,
+    dice: DiceRoll,
+    move_seq: &[Move],
+) -> String {
+    let mut value = format!("{}{}", dice.0, dice.1);
+    for &Move(from, to) in move_seq {
+        value.push(location_letter(player, from));
+        value.push(location_letter(player, to));
+    }
+
+    value
+}
+
+fn parse_move(
+    player: PlayerColor,
+    value: &str,
+) -> Option<(DiceRoll, Vec<Move>)> {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() < 2 || !chars.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let dice = parse_dice(&chars[..2].iter().collect::<String>())?;
+    let move_seq = chars[2..]
+        .chunks(2)
+        .map(|pair| {
+            Some(Move(
+                letter_location(player, pair[0])?,
+                letter_location(player, pair[1])?,
+            ))
+        })
+        .collect::<Option<Vec<Move>>>()?;
+
+    Some((dice, move_seq))
+}
+
+// properties that describe a position rather than an action
+const SETUP_PROPERTIES: &[&str] = &["AE", "AB", "AW", "PL", "CV", "CP", "DI"];
+
+// apply a node's setup properties to a game that hasn't started yet
+fn apply_setup(game: &Game, node: &SgfNode) -> Result<Game, SgfErrorKind> {
+    let board = game.board();
+    let mut counts = [
+        board.to_compact().counts(PlayerColor::Black),
+        board.to_compact().counts(PlayerColor::White),
+    ];
+
+    // points for both players' checkers, in the same layout as counts
+    let letter_indices = |values: &[String], player: PlayerColor| {
+        let mut indices = vec![];
+        for value in values {
+            let letters: Vec<char> = value.chars().collect();
+            let (first, last) = match *letters.as_slice() {
+                [letter] => (letter, letter),
+                // a range of points, e.g. "a:x"
+                [first, ':', last] if first <= last => (first, last),
+                _ => return Err(SgfErrorKind::InvalidValue("setup")),
+            };
+
+            for letter in first..=last {
+                match letter_location(player, letter) {
+                    Some(Location::Point(n)) => indices.push(n - 1),
+                    Some(Location::Bar) => indices.push(24),
+                    _ => return Err(SgfErrorKind::InvalidValue("setup")),
+                }
+            }
+        }
+
+        Ok(indices)
+    };
+
+    if let Some(values) = node.get("AE") {
+        for player in [PlayerColor::Black, PlayerColor::White] {
+            for index in letter_indices(values, player)? {
+                counts[player as usize][index] = 0;
+            }
+        }
+    }
+
+    for (id, player) in [("AB", PlayerColor::Black), ("AW", PlayerColor::White)]
+    {
+        for index in letter_indices(node.get(id).unwrap_or(&[]), player)? {
+            // checked as we go, so that a long list of values can't overflow
+            // the count
+            let count = &mut counts[player as usize][index];
+            if *count as usize >= CHECKERS_PER_PLAYER {
+                return Err(SgfErrorKind::InvalidPosition);
+            }
+            *count += 1;
+        }
+    }
+
+    let [black, white] = counts;
+    let total =
+        |counts: &[u8]| counts.iter().map(|&c| c as usize).sum::<usize>();
+    let shared = (0..24).any(|i| black[i] > 0 && white[23 - i] > 0);
+    if total(&black) > CHECKERS_PER_PLAYER
+        || total(&white) > CHECKERS_PER_PLAYER
+        || shared
+    {
+        return Err(SgfErrorKind::InvalidPosition);
+    }
+
+    let cur_player = match node.value("PL") {
+        None => board.cur_player,
+        Some("B") => PlayerColor::Black,
+        Some("W") => PlayerColor::White,
+        Some(_) => return Err(SgfErrorKind::InvalidValue("PL")),
+    };
+
+    let mut cube = *game.cube();
+    if let Some(value) = node.value("CV") {
+        cube.value = value
+            .parse()
+            .ok()
+            .filter(|value: &usize| value.is_power_of_two())
+            .ok_or(SgfErrorKind::InvalidValue("CV"))?;
+    }
+
+    if let Some(value) = node.value("CP") {
+        cube.owner = match value {
+            "c" => None,
+            "b" => Some(PlayerColor::Black),
+            "w" => Some(PlayerColor::White),
+            _ => return Err(SgfErrorKind::InvalidValue("CP")),
+        };
+    }
+
+    let phase = match node.value("DI") {
+        Some(dice) => Phase::AwaitingMove(
+            parse_dice(dice).ok_or(SgfErrorKind::InvalidValue("DI"))?,
+        ),
+        None if node.get("PL").is_some() => Phase::AwaitingRoll,
+        None => game.phase(),
+    };
+
+    Ok(Game::from_setup(Setup {
+        board: CompactBoard::from_counts(&black, &white, cur_player).to_board(),
+        cube,
+        phase,
+    }))
+}
+
+// apply the value of a B or W property, e.g. "52mhmj" or "double"
+fn apply_action(
+    game: &mut Game,
+    player: PlayerColor,
+    value: &str,
+) -> Result<(), SgfErrorKind> {
+    let action = match value {
+        "double" => Action::Double,
+        "take" => Action::Take,
+        "drop" => Action::Drop,
+        "beaver" => Action::Beaver,
+        // what was conceded is only known from RE, so resignations are
+        // applied after the game's last node
+        "resign" | "accept" | "reject" => return Ok(()),
+        value => {
+            let (dice, move_seq) = parse_move(player, value)
+                .ok_or(SgfErrorKind::InvalidValue(color_letter(player)))?;
+
+            match game.phase() {
+                Phase::OpeningRoll => {
+                    let (high, low) = (dice.0.max(dice.1), dice.0.min(dice.1));
+                    let opening_roll = match player {
+                        PlayerColor::Black => Action::OpeningRoll(high, low),
+                        PlayerColor::White => Action::OpeningRoll(low, high),
+                    };
+                    game.apply(player, opening_roll)?;
+                }
+                // the dice were already given with DI
+                Phase::AwaitingMove(rolled)
+                    if rolled == dice || rolled == (dice.1, dice.0) => {}
+                Phase::AwaitingMove(_) => {
+                    return Err(SgfErrorKind::InvalidValue("DI"))
+                }
+                _ => game.apply(player, Action::Roll(dice))?,
+            }
+
+            Action::Move(move_seq)
+        }
+    };
+
+    game.apply(player, action)?;
+    Ok(())
+}
+
+// a value in MI, e.g. 3 for "ws" in MI[length:5][game:2][ws:3][bs:0]
+fn match_info(
+    node: &SgfNode,
+    key: &str,
+) -> Result<Option<usize>, SgfErrorKind> {
+    node.get("MI")
+        .unwrap_or(&[])
+        .iter()
+        .find_map(|value| value.strip_prefix(key)?.strip_prefix(':'))
+        .map(|value| {
+            value.parse().map_err(|_| SgfErrorKind::InvalidValue("MI"))
+        })
+        .transpose()
+}
+
+// e.g. "W+2", or "B+1R" for a resignation
+fn parse_result(value: &str) -> Option<(PlayerColor, usize)> {
+    let winner = match value.get(..2)? {
+        "B+" => PlayerColor::Black,
+        "W+" => PlayerColor::White,
+        _ => return None,
+    };
+    let points = value[2..].trim_end_matches('R').parse().ok()?;
+
+    Some((winner, points))
+}
+
+// setup properties for a game that didn't start from the starting position
+fn write_setup(node: &mut SgfNode, setup: &Setup) {
+    node.push("AE", "a:y".to_string());
+
+    for player in [PlayerColor::Black, PlayerColor::White] {
+        let counts = setup.board.to_compact().counts(player);
+        let mut values = vec![];
+        for (i, &count) in counts.iter().enumerate() {
+            let location = Location::from_pip(i + 1).unwrap();
+            for _ in 0..count {
+                values.push(location_letter(player, location).to_string());
+            }
+        }
+
+        if !values.is_empty() {
+            let id = match player {
+                PlayerColor::Black => "AB",
+                PlayerColor::White => "AW",
+            };
+            node.set(id, values);
+        }
+    }
+
+    if setup.phase != Phase::OpeningRoll {
+        node.push("PL", color_letter(setup.board.cur_player).to_string());
+    }
+
+    if setup.cube.value != 1 {
+        node.push("CV", setup.cube.value.to_string());
+    }
+
+    if let Some(owner) = setup.cube.owner {
+        node.push("CP", color_letter(owner).to_lowercase());
+    }
+
+    if let Phase::AwaitingMove(dice) = setup.phase {
+        node.push("DI", format!("{}{}", dice.0, dice.1));
+    }
+}
+
+// one node per play or cube action
+fn game_nodes(game: &Game) -> Vec<SgfNode> {
+    // a setup may already include the dice for the first play
+    let mut dice = match game.setup().map(|setup| setup.phase) {
+        Some(Phase::AwaitingMove(dice)) => dice,
+        _ => (0, 0),
+    };
+    let mut nodes = vec![];
+
+    for (player, action) in game.history() {
+        let value = match action {
+            Action::OpeningRoll(black_die, white_die) => {
+                // tied opening rolls aren't recorded, and the winner's die is
+                // written first
+                dice = if black_die > white_die {
+                    (*black_die, *white_die)
+                } else {
+                    (*white_die, *black_die)
+                };
+                continue;
+            }
+            Action::Roll(roll) => {
+                dice = *roll;
+                continue;
+            }
+            Action::Move(move_seq) => format_move(*player, dice, move_seq),
+            Action::Double => "double".to_string(),
+            Action::Take => "take".to_string(),
+            Action::Drop => "drop".to_string(),
+            Action::Beaver => "beaver".to_string(),
+            // written as part of RE instead
+            Action::Resign(_) => continue,
+        };
+
+        let mut node = SgfNode::default();
+        node.push(color_letter(*player), value);
+        nodes.push(node);
+    }
+
+    nodes
+}
+
+// a match or a session of money games in gnubg's SGF format, with one game
+// tree per game. every property is kept, including ones bgrs doesn't use
+// such as gnubg's analysis, so files can be read and written back without
+// losing anything.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sgf {
+    pub trees: Vec<SgfTree>,
+}
+
+impl Sgf {
+    pub fn parse(text: &str) -> Result<Self, SgfError> {
+        let mut parser = Parser {
+            chars: text.chars().collect(),
+            pos: 0,
+            depth: 0,
+            game: 1,
+            node: 0,
+        };
+
+        let mut trees = vec![];
+        while parser.peek().is_some() {
+            trees.push(parser.tree()?);
+            parser.game += 1;
+            parser.node = 0;
+        }
+
+        if trees.is_empty() {
+            return Err(parser.error());
+        }
+
+        Ok(Self { trees })
+    }
+
+    pub fn to_sgf(&self) -> String {
+        let mut out = String::new();
+        for tree in &self.trees {
+            tree.write(&mut out);
+            out.push('\n');
+        }
+
+        out
+    }
+
+    pub fn from_match(record: &MatchRecord) -> Self {
+        let mut match_ = if record.length > 0 {
+            Some(Match::new(record.length))
+        } else {
+            None
+        };
+
+        let mut trees = vec![];
+        for (i, game) in record.games.iter().enumerate() {
+            let mut root = SgfNode::default();
+            root.push("FF", "4".to_string());
+            root.push("GM", "6".to_string());
+            root.push("CA", "UTF-8".to_string());
+            root.push("AP", "bgrs".to_string());
+
+            let (black_score, white_score) =
+                match_.as_ref().map_or((0, 0), |match_| {
+                    (match_.black_score, match_.white_score)
+                });
+            root.set(
+                "MI",
+                vec![
+                    format!("length:{}", record.length),
+                    format!("game:{}", i),
+                    format!("ws:{}", white_score),
+                    format!("bs:{}", black_score),
+                ],
+            );
+            root.push("PB", record.black_name.clone());
+            root.push("PW", record.white_name.clone());
+
+            if let Some(match_) = &match_ {
+                root.push(
+                    "RU",
+                    if match_.crawford {
+                        "Crawford:CrawfordGame"
+                    } else {
+                        "Crawford"
+                    }
+                    .to_string(),
+                );
+            }
+
+            if let (Some(result), Some(points)) = (game.result(), game.points())
+            {
+                let resigned = matches!(
+                    game.history().last(),
+                    Some((_, Action::Resign(_)))
+                );
+                root.push(
+                    "RE",
+                    format!(
+                        "{}+{}{}",
+                        color_letter(result.winner),
+                        points,
+                        if resigned { "R" } else { "" }
+                    ),
+                );
+            }
+
+            if let Some(setup) = game.setup() {
+                write_setup(&mut root, setup);
+            }
+
+            let mut nodes = vec![root];
+            nodes.extend(game_nodes(game));
+            trees.push(SgfTree {
+                nodes,
+                variations: vec![],
+            });
+
+            if let (Some(match_), Some(result)) =
+                (match_.as_mut(), game.result())
+            {
+                match_.record_result(&result, game.cube());
+            }
+        }
+
+        Self { trees }
+    }
+
+    // replay the main line of every game, checking that each action is
+    // legal. the match length and the players' names are taken from the
+    // first game, and the match starts from the score in its MI, so a file
+    // may have just some of a match's games. a game that ends before it's
+    // finished was resigned, and RE says what was conceded.
+    pub fn to_match(&self) -> Result<MatchRecord, SgfError> {
+        let mut record = MatchRecord::new(0, "", "");
+        let mut match_: Option<Match> = None;
+
+        for (i, tree) in self.trees.iter().enumerate() {
+            let nodes = tree.main_line();
+            let root = nodes[0];
+            let error = |node: usize, kind| SgfError {
+                game: i + 1,
+                node: node + 1,
+                kind,
+            };
+
+            if root.value("GM") != Some("6") {
+                return Err(error(0, SgfErrorKind::NotBackgammon));
+            }
+
+            let info = |key| match_info(root, key).map_err(|e| error(0, e));
+            let black_score = info("bs")?;
+            let white_score = info("ws")?;
+
+            if i == 0 {
+                record.length = info("length")?.unwrap_or(0);
+                record.black_name = root.value("PB").unwrap_or("").to_string();
+                record.white_name = root.value("PW").unwrap_or("").to_string();
+
+                if record.length > 0 {
+                    let crawford =
+                        root.value("RU") == Some("Crawford:CrawfordGame");
+                    let mut resumed = Match::resume(
+                        record.length,
+                        black_score.unwrap_or(0),
+                        white_score.unwrap_or(0),
+                        crawford,
+                    );
+                    resumed.game_count = info("game")?.unwrap_or(0);
+                    match_ = Some(resumed);
+                }
+            } else if let Some(match_) = &match_ {
+                let matches = |score: Option<usize>, player| match score {
+                    Some(score) => score == match_.score(player),
+                    None => true,
+                };
+                if !matches(black_score, PlayerColor::Black)
+                    || !matches(white_score, PlayerColor::White)
+                {
+                    return Err(error(0, SgfErrorKind::WrongScore));
+                }
+            }
+
+            let mut game = match match_.as_mut() {
+                Some(match_) if match_.is_over() => {
+                    return Err(error(0, SgfErrorKind::MatchOver))
+                }
+                Some(match_) => match_.start_game(),
+                None => Game::new_with_opening_roll(CubeState::new(), 0),
+            };
+
+            for (j, node) in nodes.iter().enumerate() {
+                if SETUP_PROPERTIES.iter().any(|&id| node.get(id).is_some()) {
+                    if !game.history().is_empty() {
+                        return Err(error(j, SgfErrorKind::SetupAfterStart));
+                    }
+
+                    game = apply_setup(&game, node)
+                        .map_err(|kind| error(j, kind))?;
+                }
+
+                for player in [PlayerColor::Black, PlayerColor::White] {
+                    if let Some(value) = node.value(color_letter(player)) {
+                        apply_action(&mut game, player, value)
+                            .map_err(|kind| error(j, kind))?;
+                    }
+                }
+            }
+
+            let result = root.value("RE").and_then(parse_result);
+            if let (None, Some((winner, points))) = (game.result(), result) {
+                // the loser resigned, conceding the points
+                let value = game.cube().value;
+                let kind = Some(points)
+                    .filter(|points| points % value == 0)
+                    .and_then(|points| WinKind::from_multiplier(points / value))
+                    .ok_or(error(0, SgfErrorKind::WrongResult))?;
+                game.apply(winner.inverse(), Action::Resign(kind))
+                    .map_err(|err| error(0, err.into()))?;
+            }
+
+            if let (Some(result), Some(points), Some(value)) =
+                (game.result(), game.points(), root.value("RE"))
+            {
+                if parse_result(value) != Some((result.winner, points)) {
+                    return Err(error(0, SgfErrorKind::WrongResult));
+                }
+            }
+
+            record.add_game(game, match_.as_mut());
+        }
+
+        Ok(record)
+    }
+}
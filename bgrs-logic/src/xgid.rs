@@ -0,0 +1,343 @@
+use super::{
+    BoardState, CompactBoard, CubeState, DiceRoll, Game, Match, Phase,
+    PlayerColor, Setup, CHECKERS_PER_PLAYER,
+};
+use std::error::Error;
+use std::fmt;
+
+// only implements Serialize, as InvalidField holds a static string
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum XgidError {
+    // an XGID has 10 fields separated by ':'
+    WrongFieldCount(usize),
+    // position must be 26 characters of '-', 'A'-'O' or 'a'-'o'
+    InvalidPosition,
+    TooManyCheckers,
+    // name of the field that couldn't be parsed
+    InvalidField(&'static str),
+}
+
+impl fmt::Display for XgidError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            XgidError::WrongFieldCount(count) => {
+                write!(f, "XGID should have 10 fields, not {}", count)
+            }
+            XgidError::InvalidPosition => write!(f, "invalid XGID position"),
+            XgidError::TooManyCheckers => write!(f, "too many checkers"),
+            XgidError::InvalidField(name) => {
+                write!(f, "invalid {} in XGID", name)
+            }
+        }
+    }
+}
+
+impl Error for XgidError {}
+
+// a position in eXtreme Gammon's format. XG calls black X and white O, and
+// numbers the points from X's point of view.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Xgid {
+    // cur_player is the player on roll, or the player who offered a double
+    pub board: BoardState,
+    pub cube_value: usize,
+    // None while the cube is centered
+    pub cube_owner: Option<PlayerColor>,
+    pub double_offered: bool,
+    // None if the dice haven't been rolled yet
+    pub dice: Option<DiceRoll>,
+    pub black_score: usize,
+    pub white_score: usize,
+    // 0 for money games
+    pub match_length: usize,
+    // only used in match play
+    pub crawford: bool,
+    // only used in money play
+    pub jacoby: bool,
+    pub beavers: bool,
+    pub max_cube: usize,
+}
+
+const XGID_PREFIX: &str = "XGID=";
+const XGID_FIELDS: usize = 10;
+const POSITION_LEN: usize = 26;
+// XG's usual limit
+const DEFAULT_MAX_CUBE: usize = 1024;
+
+fn encode_count(count: u8, player: PlayerColor) -> char {
+    match (count, player) {
+        (0, _) => '-',
+        (_, PlayerColor::Black) => (b'A' + count - 1) as char,
+        (_, PlayerColor::White) => (b'a' + count - 1) as char,
+    }
+}
+
+fn decode_count(c: char) -> Option<(u8, PlayerColor)> {
+    match c {
+        '-' => Some((0, PlayerColor::Black)),
+        'A'..='O' => Some((c as u8 - b'A' + 1, PlayerColor::Black)),
+        'a'..='o' => Some((c as u8 - b'a' + 1, PlayerColor::White)),
+        _ => None,
+    }
+}
+
+fn encode_player(player: PlayerColor) -> &'static str {
+    match player {
+        PlayerColor::Black => "1",
+        PlayerColor::White => "-1",
+    }
+}
+
+fn parse_number(s: &str, name: &'static str) -> Result<usize, XgidError> {
+    s.parse().map_err(|_| XgidError::InvalidField(name))
+}
+
+fn parse_player(s: &str, name: &'static str) -> Result<PlayerColor, XgidError> {
+    match s {
+        "1" => Ok(PlayerColor::Black),
+        "-1" => Ok(PlayerColor::White),
+        _ => Err(XgidError::InvalidField(name)),
+    }
+}
+
+fn parse_log2(s: &str, name: &'static str) -> Result<usize, XgidError> {
+    match parse_number(s, name)? {
+        log2 if log2 < 16 => Ok(1 << log2),
+        _ => Err(XgidError::InvalidField(name)),
+    }
+}
+
+impl Xgid {
+    pub fn from_game(game: &Game, match_: Option<&Match>) -> Self {
+        let cube = game.cube();
+
+        Self {
+            board: game.board().clone(),
+            cube_value: cube.value,
+            cube_owner: cube.owner,
+            double_offered: game.phase() == Phase::CubeDecision,
+            dice: match game.phase() {
+                Phase::AwaitingMove(dice) => Some(dice),
+                _ => None,
+            },
+            black_score: match_.map_or(0, |m| m.black_score),
+            white_score: match_.map_or(0, |m| m.white_score),
+            match_length: match_.map_or(0, |m| m.length),
+            crawford: match_.is_some_and(|m| m.crawford),
+            jacoby: false,
+            beavers: match_.is_none() && cube.beavers_allowed,
+            max_cube: DEFAULT_MAX_CUBE,
+        }
+    }
+
+    // player who has to act next: the player on roll, or the player who has
+    // to answer a double
+    pub fn turn(&self) -> PlayerColor {
+        if self.double_offered {
+            self.board.cur_player.inverse()
+        } else {
+            self.board.cur_player
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let compact = self.board.to_compact();
+        let black = compact.counts(PlayerColor::Black);
+        let white = compact.counts(PlayerColor::White);
+
+        // white's bar, the points from 1 to 24 from black's point of view,
+        // and black's bar. white's point n is black's point 25 - n.
+        let position: String = (0..POSITION_LEN)
+            .map(|i| {
+                if i > 0 && black[i - 1] > 0 {
+                    encode_count(black[i - 1], PlayerColor::Black)
+                } else if i < 25 {
+                    encode_count(white[24 - i], PlayerColor::White)
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+
+        let cube_position = match self.cube_owner {
+            None => "0",
+            Some(player) => encode_player(player),
+        };
+
+        let dice = match self.dice {
+            _ if self.double_offered => "D".to_string(),
+            Some((die_1, die_2)) => format!("{}{}", die_1, die_2),
+            None => "00".to_string(),
+        };
+
+        let rules = if self.match_length > 0 {
+            self.crawford as usize
+        } else {
+            self.jacoby as usize | (self.beavers as usize) << 1
+        };
+
+        format!(
+            "{}{}:{}:{}:{}:{}:{}:{}:{}:{}:{}",
+            XGID_PREFIX,
+            position,
+            self.cube_value.trailing_zeros(),
+            cube_position,
+            encode_player(self.turn()),
+            dice,
+            self.black_score,
+            self.white_score,
+            rules,
+            self.match_length,
+            self.max_cube.trailing_zeros(),
+        )
+    }
+
+    // the "XGID=" prefix is optional
+    pub fn decode(xgid: &str) -> Result<Self, XgidError> {
+        let xgid = xgid.trim();
+        let xgid = xgid.strip_prefix(XGID_PREFIX).unwrap_or(xgid);
+
+        let fields: Vec<&str> = xgid.split(':').collect();
+        if fields.len() != XGID_FIELDS {
+            return Err(XgidError::WrongFieldCount(fields.len()));
+        }
+
+        let position: Vec<char> = fields[0].chars().collect();
+        if position.len() != POSITION_LEN {
+            return Err(XgidError::InvalidPosition);
+        }
+
+        let mut black = [0u8; 25];
+        let mut white = [0u8; 25];
+        for (i, &c) in position.iter().enumerate() {
+            let (count, player) =
+                decode_count(c).ok_or(XgidError::InvalidPosition)?;
+            if count == 0 {
+                continue;
+            }
+
+            match player {
+                // white's checkers can't be on black's bar, and vice versa
+                PlayerColor::Black if i == 0 => {
+                    return Err(XgidError::InvalidPosition)
+                }
+                PlayerColor::White if i == 25 => {
+                    return Err(XgidError::InvalidPosition)
+                }
+                PlayerColor::Black => black[i - 1] = count,
+                PlayerColor::White => white[24 - i] = count,
+            }
+        }
+
+        for counts in [black, white].iter() {
+            let total: usize = counts.iter().map(|&c| c as usize).sum();
+            if total > CHECKERS_PER_PLAYER {
+                return Err(XgidError::TooManyCheckers);
+            }
+        }
+
+        let cube_value = parse_log2(fields[1], "cube value")?;
+        let cube_owner = match fields[2] {
+            "0" => None,
+            s => Some(parse_player(s, "cube position")?),
+        };
+        let turn = parse_player(fields[3], "turn")?;
+
+        let (dice, double_offered) = match fields[4] {
+            "00" => (None, false),
+            "D" => (None, true),
+            s => {
+                // anything that isn't a digit becomes an invalid die
+                let dice: Vec<usize> = s
+                    .chars()
+                    .map(|c| c.to_digit(10).map_or(0, |d| d as usize))
+                    .collect();
+                match *dice.as_slice() {
+                    [die_1, die_2]
+                        if (1..=6).contains(&die_1)
+                            && (1..=6).contains(&die_2) =>
+                    {
+                        (Some((die_1, die_2)), false)
+                    }
+                    _ => return Err(XgidError::InvalidField("dice")),
+                }
+            }
+        };
+
+        let black_score = parse_number(fields[5], "score")?;
+        let white_score = parse_number(fields[6], "score")?;
+        let rules = parse_number(fields[7], "crawford/jacoby")?;
+        let match_length = parse_number(fields[8], "match length")?;
+        let max_cube = parse_log2(fields[9], "max cube")?;
+
+        if rules > 3 || (match_length > 0 && rules > 1) {
+            return Err(XgidError::InvalidField("crawford/jacoby"));
+        }
+
+        // the board is from the point of view of whoever is on roll, even if
+        // the other player is answering a double
+        let cur_player = if double_offered { turn.inverse() } else { turn };
+
+        Ok(Self {
+            board: CompactBoard::from_counts(&black, &white, cur_player)
+                .to_board(),
+            cube_value,
+            cube_owner,
+            double_offered,
+            dice,
+            black_score,
+            white_score,
+            match_length,
+            crawford: match_length > 0 && rules & 1 != 0,
+            jacoby: match_length == 0 && rules & 1 != 0,
+            beavers: match_length == 0 && rules & 2 != 0,
+            max_cube,
+        })
+    }
+
+    // the match this position is part of, or None for money games
+    pub fn to_match(&self) -> Option<Match> {
+        if self.match_length == 0 {
+            return None;
+        }
+
+        Some(Match::resume(
+            self.match_length,
+            self.black_score,
+            self.white_score,
+            self.crawford,
+        ))
+    }
+
+    pub fn to_cube(&self) -> CubeState {
+        CubeState {
+            value: self.cube_value,
+            owner: self.cube_owner,
+            offered_by: if self.double_offered {
+                Some(self.board.cur_player)
+            } else {
+                None
+            },
+            enabled: !self.crawford,
+            beavers_allowed: self.beavers,
+        }
+    }
+
+    pub fn to_game(&self) -> Game {
+        let phase = if self.double_offered {
+            Phase::CubeDecision
+        } else if let Some(dice) = self.dice {
+            Phase::AwaitingMove(dice)
+        } else {
+            Phase::AwaitingRoll
+        };
+
+        Game::from_setup(Setup {
+            board: self.board.clone(),
+            cube: self.to_cube(),
+            phase,
+        })
+    }
+}
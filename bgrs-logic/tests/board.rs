@@ -0,0 +1,93 @@
+extern crate bgrs_logic;
+
+mod common;
+
+use bgrs_logic::{
+    BoardState, GameResult, Location, Move, PlayerColor, WinKind,
+};
+use common::{point, position};
+
+const BLACK: PlayerColor = PlayerColor::Black;
+const WHITE: PlayerColor = PlayerColor::White;
+
+#[test]
+fn results() {
+    assert_eq!(BoardState::new_starting_state(BLACK).get_result(), None);
+    // one checker left isn't a win yet
+    assert_eq!(
+        position(&[(point(1), 1)], &[(point(6), 15)]).get_result(),
+        None
+    );
+
+    let result =
+        |white: &[(Location, usize)]| position(&[], white).get_result();
+
+    // white has borne off a checker
+    assert_eq!(
+        result(&[(point(6), 14)]),
+        Some(GameResult::new(BLACK, WinKind::Single))
+    );
+    // none borne off, but none left in black's home board either
+    assert_eq!(
+        result(&[(point(18), 1), (point(6), 14)]),
+        Some(GameResult::new(BLACK, WinKind::Gammon))
+    );
+    // still on the bar
+    assert_eq!(
+        result(&[(Location::Bar, 1), (point(6), 14)]),
+        Some(GameResult::new(BLACK, WinKind::Backgammon))
+    );
+    // still in black's home board
+    assert_eq!(
+        result(&[(point(19), 1), (point(6), 14)]),
+        Some(GameResult::new(BLACK, WinKind::Backgammon))
+    );
+
+    // and the same for white
+    let board = position(&[(point(24), 1), (point(6), 14)], &[]);
+    assert_eq!(
+        board.get_result(),
+        Some(GameResult::new(WHITE, WinKind::Backgammon))
+    );
+}
+
+#[test]
+fn locations() {
+    for &player in &[BLACK, WHITE] {
+        for pip in 0..=25 {
+            let location = Location::from_pip(pip).unwrap();
+            assert_eq!(location.pip(), pip);
+            match location.to_index(player) {
+                Some(index) => {
+                    assert_eq!(Location::from_index(player, index), location)
+                }
+                None => assert_eq!(location, Location::Off),
+            }
+        }
+    }
+    assert_eq!(Location::from_pip(26), None);
+
+    // each player's bar is at their own end of the board
+    assert_eq!(Location::Bar.to_index(BLACK), Some(0));
+    assert_eq!(Location::Bar.to_index(WHITE), Some(25));
+    assert_eq!(point(1).to_index(BLACK), Some(24));
+    assert_eq!(point(1).to_index(WHITE), Some(1));
+    // and the other player's bar is where checkers are borne off
+    assert_eq!(Location::from_index(BLACK, 25), Location::Off);
+    assert_eq!(Location::from_index(WHITE, 0), Location::Off);
+}
+
+#[test]
+fn bearing_off() {
+    let mut board =
+        position(&[(point(2), 1), (point(1), 1)], &[(point(6), 15)]);
+    board.apply_move(Move(point(2), Location::Off));
+    assert_eq!(board.borne_off(BLACK), 14);
+    assert_eq!(board.borne_off(WHITE), 0);
+    assert_eq!(board.get_winner(), None);
+
+    // the last checker off wins
+    board.apply_move(Move(point(1), Location::Off));
+    assert_eq!(board.borne_off(BLACK), 15);
+    assert_eq!(board.get_winner(), Some(BLACK));
+}
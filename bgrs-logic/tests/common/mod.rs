@@ -0,0 +1,39 @@
+// helpers shared by the integration tests. each test crate uses only some of
+// them.
+#![allow(dead_code)]
+
+use bgrs_logic::{BoardState, Location, PlayerColor, PointState};
+
+pub fn point(n: usize) -> Location {
+    Location::Point(n)
+}
+
+// a board with checkers on the given locations, numbered from each player's
+// point of view, and black on roll. the rest of the checkers are borne off.
+pub fn position(
+    black: &[(Location, usize)],
+    white: &[(Location, usize)],
+) -> BoardState {
+    let mut points = [PointState::new(0, PlayerColor::Black); 26];
+    points[25] = PointState::new(0, PlayerColor::White);
+
+    for &(player, checkers) in
+        &[(PlayerColor::Black, black), (PlayerColor::White, white)]
+    {
+        for &(location, count) in checkers {
+            let index = location.to_index(player).unwrap();
+            points[index] = PointState::new(count, player);
+        }
+    }
+
+    let on_board = |checkers: &[(Location, usize)]| {
+        checkers.iter().map(|&(_, count)| count).sum::<usize>()
+    };
+
+    BoardState {
+        points,
+        cur_player: PlayerColor::Black,
+        black_borne_off: 15 - on_board(black),
+        white_borne_off: 15 - on_board(white),
+    }
+}
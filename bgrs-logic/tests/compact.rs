@@ -0,0 +1,96 @@
+extern crate bgrs_logic;
+
+mod common;
+
+use bgrs_logic::{
+    BoardState, CompactBoard, DiceSource, Location, PlayerColor, PointState,
+    SeededDice,
+};
+use common::position;
+
+const BLACK: PlayerColor = PlayerColor::Black;
+const WHITE: PlayerColor = PlayerColor::White;
+
+fn assert_round_trip(board: &BoardState) {
+    let compact = board.to_compact();
+    assert_eq!(&compact.to_board(), board);
+    assert_eq!(CompactBoard::from(board), compact);
+    assert_eq!(BoardState::from(compact), *board);
+    assert_eq!(compact.cur_player, board.cur_player);
+
+    for &player in &[BLACK, WHITE] {
+        for pip in 0..=25 {
+            let location = Location::from_pip(pip).unwrap();
+            assert_eq!(
+                compact.checkers_at(player, location),
+                board.checkers_at(player, location)
+            );
+        }
+    }
+}
+
+#[test]
+fn starting_position() {
+    let board = BoardState::new_starting_state(BLACK);
+    assert_round_trip(&board);
+
+    let counts = board.to_compact().counts(BLACK);
+    assert_eq!(counts[5], 5);
+    assert_eq!(counts[7], 3);
+    assert_eq!(counts[12], 5);
+    assert_eq!(counts[23], 2);
+    assert_eq!(counts[24], 0);
+}
+
+#[test]
+fn bar_and_borne_off() {
+    let mut board = position(
+        &[(Location::Bar, 2), (Location::Point(3), 4)],
+        &[(Location::Bar, 1), (Location::Point(20), 3)],
+    );
+    board.cur_player = WHITE;
+    assert_round_trip(&board);
+
+    let compact = board.to_compact();
+    assert_eq!(compact.checkers_at(BLACK, Location::Bar), 2);
+    assert_eq!(compact.checkers_at(BLACK, Location::Off), 9);
+    assert_eq!(compact.checkers_at(WHITE, Location::Bar), 1);
+    assert_eq!(compact.checkers_at(WHITE, Location::Off), 11);
+    assert_eq!(compact.counts(BLACK)[24], 2);
+
+    // everything borne off, and everything on the bar
+    assert_round_trip(&position(&[], &[(Location::Bar, 15)]));
+    assert_round_trip(&position(&[(Location::Bar, 15)], &[]));
+}
+
+#[test]
+fn random_positions() {
+    let mut dice = SeededDice::new(3);
+
+    for _ in 0..20 {
+        let mut board = BoardState::new_starting_state(BLACK);
+        while board.get_result().is_none() {
+            assert_round_trip(&board);
+
+            let roll = (dice.roll_die(), dice.roll_die());
+            let plays = board.get_distinct_plays(roll);
+            if !plays.is_empty() {
+                board = plays[dice.roll_die() % plays.len()].result.clone();
+            }
+            board.end_turn();
+        }
+
+        assert_round_trip(&board);
+    }
+}
+
+#[test]
+fn too_many_checkers() {
+    // an invalid board with 15 checkers on every point and the bar, 375 in
+    // all, still says none are borne off, instead of overflowing
+    let mut board = position(&[], &[]);
+    for point in board.points[..25].iter_mut() {
+        *point = PointState::new(15, BLACK);
+    }
+    assert_eq!(board.to_compact().checkers_at(BLACK, Location::Off), 0);
+}
@@ -0,0 +1,98 @@
+extern crate bgrs_logic;
+
+use bgrs_logic::{CubeError, CubeState, GameResult, PlayerColor, WinKind};
+
+const BLACK: PlayerColor = PlayerColor::Black;
+const WHITE: PlayerColor = PlayerColor::White;
+
+#[test]
+fn double_and_take() {
+    let mut cube = CubeState::new();
+    assert!(cube.is_centered());
+    assert_eq!(cube.check_double(BLACK, false), Ok(()));
+    assert_eq!(cube.check_double(WHITE, false), Ok(()));
+
+    cube.double(BLACK, false).unwrap();
+    assert_eq!(cube.offered_by, Some(BLACK));
+    // the cube only changes hands once the double is taken
+    assert_eq!(cube.value, 1);
+    assert!(cube.is_centered());
+
+    cube.take(WHITE).unwrap();
+    assert_eq!(cube.value, 2);
+    assert_eq!(cube.owner, Some(WHITE));
+    assert_eq!(cube.offered_by, None);
+
+    // only the owner may redouble
+    assert_eq!(
+        cube.check_double(BLACK, false),
+        Err(CubeError::NotCubeOwner)
+    );
+    cube.double(WHITE, false).unwrap();
+    cube.take(BLACK).unwrap();
+    assert_eq!(cube.value, 4);
+    assert_eq!(cube.owner, Some(BLACK));
+
+    let result = GameResult::new(BLACK, WinKind::Gammon);
+    assert_eq!(cube.points(&result), 8);
+}
+
+#[test]
+fn drop() {
+    let mut cube = CubeState::new();
+    cube.double(WHITE, false).unwrap();
+    assert_eq!(
+        cube.drop_double(BLACK),
+        Ok(GameResult::new(WHITE, WinKind::Single))
+    );
+    // the doubler wins at the value before the double
+    assert_eq!(cube.value, 1);
+    assert_eq!(cube.offered_by, None);
+}
+
+#[test]
+fn illegal_doubles() {
+    let mut cube = CubeState::new();
+    assert_eq!(cube.double(BLACK, true), Err(CubeError::AlreadyRolled));
+
+    cube.double(BLACK, false).unwrap();
+    assert_eq!(
+        cube.double(WHITE, false),
+        Err(CubeError::DoubleAlreadyOffered)
+    );
+    assert_eq!(cube.take(BLACK), Err(CubeError::OwnDouble));
+    assert_eq!(cube.drop_double(BLACK), Err(CubeError::OwnDouble));
+    assert_eq!(cube.beaver(BLACK), Err(CubeError::OwnDouble));
+
+    let mut cube = CubeState::new();
+    assert_eq!(cube.take(WHITE), Err(CubeError::NoDoubleOffered));
+    assert_eq!(cube.drop_double(WHITE), Err(CubeError::NoDoubleOffered));
+    assert_eq!(cube.beaver(WHITE), Err(CubeError::NoDoubleOffered));
+
+    cube.enabled = false;
+    assert_eq!(cube.double(BLACK, false), Err(CubeError::CubeDisabled));
+    // a disabled cube is reported first, even after rolling
+    assert_eq!(cube.double(BLACK, true), Err(CubeError::CubeDisabled));
+}
+
+#[test]
+fn beavers() {
+    let mut cube = CubeState::new();
+    cube.double(BLACK, false).unwrap();
+    cube.beaver(WHITE).unwrap();
+    // the player who beavered keeps the cube at 4
+    assert_eq!(cube.value, 4);
+    assert_eq!(cube.owner, Some(WHITE));
+    assert_eq!(cube.offered_by, None);
+
+    let mut cube = CubeState {
+        beavers_allowed: false,
+        ..CubeState::new()
+    };
+    cube.double(BLACK, false).unwrap();
+    assert_eq!(cube.beaver(WHITE), Err(CubeError::BeaverNotAllowed));
+    // the double can still be answered normally
+    assert_eq!(cube.offered_by, Some(BLACK));
+    cube.take(WHITE).unwrap();
+    assert_eq!(cube.value, 2);
+}
@@ -0,0 +1,96 @@
+extern crate bgrs_logic;
+
+use bgrs_logic::{
+    Action, CubeState, DiceSource, DieRoll, Game, ManualDice, Phase,
+    PlayerColor, ScriptedDice, SeededDice,
+};
+
+fn rolls(dice: &mut dyn DiceSource, count: usize) -> Vec<DieRoll> {
+    (0..count).map(|_| dice.roll_die()).collect()
+}
+
+#[test]
+fn seeded_dice() {
+    // the same seed always gives the same rolls
+    let first = rolls(&mut SeededDice::new(42), 100);
+    assert_eq!(rolls(&mut SeededDice::new(42), 100), first);
+    assert_eq!(SeededDice::new(42).seed(), Some(42));
+
+    // and nearby seeds give different ones
+    assert_ne!(rolls(&mut SeededDice::new(43), 100), first);
+    assert_ne!(rolls(&mut SeededDice::new(0), 100), first);
+
+    // rolls are pinned down, so that saved seeds keep replaying the same
+    // games
+    assert_eq!(
+        rolls(&mut SeededDice::new(0), 10),
+        vec![5, 1, 6, 2, 1, 3, 2, 1, 2, 5]
+    );
+
+    // a copy carries on from where the original was
+    let mut dice = SeededDice::new(7);
+    rolls(&mut dice, 5);
+    let mut copy = dice.clone();
+    assert_eq!(rolls(&mut copy, 20), rolls(&mut dice, 20));
+}
+
+#[test]
+fn fair_dice() {
+    let mut counts = [0; 6];
+    let mut dice = SeededDice::new(1);
+    for _ in 0..60000 {
+        let die = dice.roll_die();
+        assert!((1..=6).contains(&die));
+        counts[die - 1] += 1;
+    }
+
+    for &count in &counts {
+        assert!((9000..11000).contains(&count), "{:?}", counts);
+    }
+}
+
+#[test]
+fn replay_from_seed() {
+    // a game played with seeded dice can be replayed from its seed
+    let play = |dice: &mut dyn DiceSource| {
+        let mut game = Game::new_with_opening_roll(CubeState::new(), 0);
+        while game.history().len() < 40 && game.result().is_none() {
+            let player = game.player_to_act().unwrap_or(PlayerColor::Black);
+            match game.phase() {
+                Phase::AwaitingMove(_) => {
+                    let play = game.legal_plays().into_iter().next();
+                    let action = Action::Move(play.unwrap_or_default());
+                    game.apply(player, action).unwrap();
+                }
+                _ => {
+                    game.roll(player, dice).unwrap();
+                }
+            }
+        }
+
+        game
+    };
+
+    let game = play(&mut SeededDice::new(5));
+    assert_eq!(game.dice_seed(), Some(5));
+    let mut dice = SeededDice::new(game.dice_seed().unwrap());
+    assert_eq!(play(&mut dice), game);
+}
+
+#[test]
+fn other_sources() {
+    let mut dice = ScriptedDice::from_rolls(&[(3, 1), (6, 6)]);
+    assert_eq!(dice.remaining(), 4);
+    assert_eq!(dice.roll_dice(), (3, 1));
+    assert_eq!(dice.roll_die(), 6);
+    assert_eq!(dice.remaining(), 1);
+    assert_eq!(dice.seed(), None);
+
+    let mut next = 0;
+    let mut dice = ManualDice::new(|| {
+        next = next % 6 + 1;
+        next
+    });
+    assert_eq!(rolls(&mut dice, 7), vec![1, 2, 3, 4, 5, 6, 1]);
+    assert_eq!(dice.seed(), None);
+}
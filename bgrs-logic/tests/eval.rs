@@ -0,0 +1,147 @@
+extern crate bgrs_logic;
+
+mod common;
+
+use bgrs_logic::{
+    Action, BoardState, CubeState, Evaluator, Game, GameResult,
+    HeuristicEvaluator, Phase, PlayerColor, PointState, Probabilities,
+    SeededDice, WinKind,
+};
+use common::{point, position};
+
+const BLACK: PlayerColor = PlayerColor::Black;
+const WHITE: PlayerColor = PlayerColor::White;
+
+// the same position with the players' colors swapped
+fn mirror(board: &BoardState) -> BoardState {
+    let mut points = board.points;
+    for (index, point) in board.points.iter().enumerate() {
+        points[25 - index] =
+            PointState::new(point.checker_count, point.checker_color.inverse());
+    }
+
+    BoardState {
+        points,
+        cur_player: board.cur_player.inverse(),
+        black_borne_off: board.white_borne_off,
+        white_borne_off: board.black_borne_off,
+    }
+}
+
+fn assert_consistent(probs: &Probabilities) {
+    assert!(0.0 <= probs.win && probs.win <= 1.0);
+    assert!(0.0 <= probs.win_backgammon);
+    assert!(probs.win_backgammon <= probs.win_gammon);
+    assert!(probs.win_gammon <= probs.win);
+    assert!(0.0 <= probs.lose_backgammon);
+    assert!(probs.lose_backgammon <= probs.lose_gammon);
+    assert!(probs.lose_gammon <= 1.0 - probs.win);
+    assert!(-3.0 <= probs.equity() && probs.equity() <= 3.0);
+}
+
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+}
+
+#[test]
+fn starting_position() {
+    let evaluator = HeuristicEvaluator::default();
+    let probs = evaluator.evaluate(&BoardState::new_starting_state(BLACK));
+
+    assert_consistent(&probs);
+    // a little better than even for the player on roll
+    assert!(probs.win > 0.5 && probs.win < 0.6);
+    assert!(probs.win_gammon > 0.0 && probs.lose_gammon > 0.0);
+}
+
+#[test]
+fn finished_games() {
+    let evaluator = HeuristicEvaluator::default();
+
+    // white hasn't borne off, and has a checker in black's home board
+    let mut board = position(&[], &[(point(3), 14), (point(20), 1)]);
+    let won = evaluator.evaluate(&board);
+    assert_eq!(
+        won,
+        Probabilities::from_result(
+            &GameResult::new(BLACK, WinKind::Backgammon),
+            BLACK
+        )
+    );
+    assert_close(won.equity(), 3.0);
+
+    board.cur_player = WHITE;
+    let lost = evaluator.evaluate(&board);
+    assert_eq!(lost, won.invert());
+    assert_close(lost.equity(), -3.0);
+
+    let single = evaluator.evaluate(&position(&[], &[(point(3), 2)]));
+    assert_eq!(single.win, 1.0);
+    assert_eq!(single.win_gammon, 0.0);
+    assert_close(single.equity(), 1.0);
+}
+
+#[test]
+fn races() {
+    let evaluator = HeuristicEvaluator::default();
+
+    // black is 15 pips ahead and on roll
+    let black = (2..=6).map(|n| (point(n), 3)).collect::<Vec<_>>();
+    let white = (3..=7).map(|n| (point(n), 3)).collect::<Vec<_>>();
+    let mut board = position(&black, &white);
+    let ahead = evaluator.evaluate(&board);
+    assert_consistent(&ahead);
+    assert!(ahead.win > 0.9);
+    // everyone's already home
+    assert!(ahead.win_gammon < 0.05);
+    assert!(ahead.lose_gammon < 0.05);
+
+    board.cur_player = WHITE;
+    let behind = evaluator.evaluate(&board);
+    assert!(behind.win < 0.25);
+
+    // all of white's checkers are still far from home
+    let gammonish =
+        evaluator.evaluate(&position(&[(point(1), 1)], &[(point(12), 15)]));
+    assert!(gammonish.win > 0.99);
+    assert!(gammonish.win_gammon > 0.5);
+}
+
+#[test]
+fn random_positions() {
+    let evaluator = HeuristicEvaluator::default();
+    let mut dice = SeededDice::new(7);
+
+    for _ in 0..10 {
+        let mut game = Game::new_with_opening_roll(CubeState::new(), 0);
+
+        while !matches!(game.phase(), Phase::GameOver(_)) {
+            let player = game.player_to_act().unwrap_or(BLACK);
+            match game.phase() {
+                Phase::AwaitingMove(_) => {
+                    let plays = game.legal_plays();
+                    let play = plays
+                        .get(game.history().len() % plays.len().max(1))
+                        .cloned();
+                    game.apply(player, Action::Move(play.unwrap_or_default()))
+                        .unwrap();
+                }
+                _ => {
+                    game.roll(player, &mut dice).unwrap();
+                }
+            }
+
+            let board = game.board();
+            let probs = evaluator.evaluate(board);
+            assert_consistent(&probs);
+
+            // the evaluation doesn't depend on which color is which
+            let mirrored = evaluator.evaluate(&mirror(board));
+            assert_close(mirrored.win, probs.win);
+            assert_close(mirrored.win_gammon, probs.win_gammon);
+            assert_close(mirrored.lose_backgammon, probs.lose_backgammon);
+        }
+
+        assert!(game.board().get_result().is_some());
+    }
+}
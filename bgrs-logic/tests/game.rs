@@ -0,0 +1,185 @@
+extern crate bgrs_logic;
+
+mod common;
+
+use bgrs_logic::{
+    opening_roll_winner, Action, CubeError, CubeState, Game, GameError,
+    GameResult, Move, Phase, PlayError, PlayerColor, WinKind,
+};
+use common::point;
+
+const BLACK: PlayerColor = PlayerColor::Black;
+const WHITE: PlayerColor = PlayerColor::White;
+
+// a game where black is to play 31
+fn game_awaiting_move() -> Game {
+    let mut game = Game::new_with_opening_roll(CubeState::new(), 0);
+    game.apply(BLACK, Action::OpeningRoll(3, 1)).unwrap();
+    game
+}
+
+#[test]
+fn turns() {
+    let mut game = game_awaiting_move();
+    assert_eq!(game.phase(), Phase::AwaitingMove((3, 1)));
+    assert_eq!(game.player_to_act(), Some(BLACK));
+
+    let play = vec![Move(point(8), point(5)), Move(point(6), point(5))];
+    assert_eq!(
+        game.apply(WHITE, Action::Move(play.clone())),
+        Err(GameError::NotYourTurn)
+    );
+    game.apply(BLACK, Action::Move(play)).unwrap();
+    assert_eq!(game.phase(), Phase::AwaitingRoll);
+    assert_eq!(game.player_to_act(), Some(WHITE));
+
+    game.apply(WHITE, Action::Double).unwrap();
+    assert_eq!(game.phase(), Phase::CubeDecision);
+    // the player being doubled answers
+    assert_eq!(game.player_to_act(), Some(BLACK));
+    assert_eq!(game.apply(WHITE, Action::Take), Err(GameError::NotYourTurn));
+    game.apply(BLACK, Action::Take).unwrap();
+    assert_eq!(game.cube().value, 2);
+    assert_eq!(game.player_to_act(), Some(WHITE));
+    assert_eq!(game.history().len(), 4);
+}
+
+#[test]
+fn wrong_phase() {
+    let wrong = Err(GameError::WrongPhase);
+
+    // nothing but the opening roll before the game starts
+    let mut game = Game::new_with_opening_roll(CubeState::new(), 0);
+    assert_eq!(game.player_to_act(), None);
+    assert_eq!(game.apply(BLACK, Action::Roll((3, 1))), wrong);
+    assert_eq!(game.apply(BLACK, Action::Double), wrong);
+    assert_eq!(game.apply(WHITE, Action::Move(vec![])), wrong);
+    assert_eq!(game.apply(WHITE, Action::Take), wrong);
+
+    // after rolling, only a play
+    let mut game = game_awaiting_move();
+    assert_eq!(game.apply(BLACK, Action::OpeningRoll(3, 1)), wrong);
+    assert_eq!(game.apply(BLACK, Action::Roll((3, 1))), wrong);
+    assert_eq!(game.apply(BLACK, Action::Take), wrong);
+    assert_eq!(game.apply(BLACK, Action::Drop), wrong);
+    assert_eq!(
+        game.apply(BLACK, Action::Double),
+        Err(GameError::Cube(CubeError::AlreadyRolled))
+    );
+
+    // before rolling, no play and no answer to a double
+    let mut game = Game::new(WHITE, CubeState::new());
+    assert_eq!(game.apply(WHITE, Action::Move(vec![])), wrong);
+    assert_eq!(game.apply(WHITE, Action::Take), wrong);
+    assert_eq!(game.apply(WHITE, Action::Beaver), wrong);
+    assert_eq!(game.apply(WHITE, Action::OpeningRoll(6, 1)), wrong);
+
+    // a double has to be answered before anything else
+    game.apply(WHITE, Action::Double).unwrap();
+    assert_eq!(game.apply(BLACK, Action::Roll((6, 1))), wrong);
+    assert_eq!(game.apply(BLACK, Action::Double), wrong);
+    assert_eq!(game.apply(BLACK, Action::Move(vec![])), wrong);
+
+    // and nothing happens after the game is over
+    game.apply(BLACK, Action::Drop).unwrap();
+    assert_eq!(game.result(), Some(GameResult::new(WHITE, WinKind::Single)));
+    assert_eq!(game.player_to_act(), None);
+    assert_eq!(game.apply(WHITE, Action::Roll((6, 1))), wrong);
+    assert_eq!(game.apply(BLACK, Action::Roll((6, 1))), wrong);
+    assert_eq!(game.apply(WHITE, Action::Resign(WinKind::Single)), wrong);
+    assert_eq!(game.history().len(), 2);
+}
+
+#[test]
+fn rejected_actions() {
+    let mut game = game_awaiting_move();
+    assert_eq!(
+        game.apply(BLACK, Action::Move(vec![Move(point(8), point(5))])),
+        Err(GameError::IllegalPlay(PlayError::TooFewMoves {
+            required: 2,
+            doubles: false,
+        }))
+    );
+
+    let mut game = Game::new(BLACK, CubeState::new());
+    assert_eq!(
+        game.apply(BLACK, Action::Roll((0, 3))),
+        Err(GameError::InvalidDie)
+    );
+    assert_eq!(
+        game.apply(BLACK, Action::Roll((3, 7))),
+        Err(GameError::InvalidDie)
+    );
+
+    let mut game = Game::new(
+        BLACK,
+        CubeState {
+            enabled: false,
+            ..CubeState::new()
+        },
+    );
+    assert_eq!(
+        game.apply(BLACK, Action::Double),
+        Err(GameError::Cube(CubeError::CubeDisabled))
+    );
+
+    // rejected actions leave the game as it was
+    assert_eq!(game.phase(), Phase::AwaitingRoll);
+    assert!(game.history().is_empty());
+}
+
+#[test]
+fn opening_roll() {
+    assert_eq!(opening_roll_winner(5, 2), Some(BLACK));
+    assert_eq!(opening_roll_winner(1, 6), Some(WHITE));
+    assert_eq!(opening_roll_winner(4, 4), None);
+
+    // the winner plays both dice
+    let mut game = Game::new_with_opening_roll(CubeState::new(), 0);
+    game.apply(WHITE, Action::OpeningRoll(2, 6)).unwrap();
+    assert_eq!(game.phase(), Phase::AwaitingMove((2, 6)));
+    assert_eq!(game.player_to_act(), Some(WHITE));
+    assert_eq!(game.board().cur_player, WHITE);
+
+    let mut game = Game::new_with_opening_roll(CubeState::new(), 0);
+    assert_eq!(
+        game.apply(BLACK, Action::OpeningRoll(0, 3)),
+        Err(GameError::InvalidDie)
+    );
+    assert_eq!(
+        game.apply(BLACK, Action::OpeningRoll(7, 3)),
+        Err(GameError::InvalidDie)
+    );
+}
+
+#[test]
+fn opening_roll_ties() {
+    // ties are rolled again, and the cube stays put without automatic
+    // doubles
+    let mut game = Game::new_with_opening_roll(CubeState::new(), 0);
+    game.apply(BLACK, Action::OpeningRoll(3, 3)).unwrap();
+    game.apply(WHITE, Action::OpeningRoll(5, 5)).unwrap();
+    assert_eq!(game.phase(), Phase::OpeningRoll);
+    assert_eq!(game.player_to_act(), None);
+    assert_eq!(game.cube().value, 1);
+    game.apply(BLACK, Action::OpeningRoll(4, 1)).unwrap();
+    assert_eq!(game.phase(), Phase::AwaitingMove((4, 1)));
+    assert_eq!(game.history().len(), 3);
+
+    // each tie doubles the cube, up to the limit
+    let mut game = Game::new_with_opening_roll(CubeState::new(), 2);
+    for _ in 0..3 {
+        game.apply(BLACK, Action::OpeningRoll(6, 6)).unwrap();
+    }
+    assert_eq!(game.cube().value, 4);
+    assert!(game.cube().is_centered());
+
+    // but not when the cube can't be used
+    let cube = CubeState {
+        enabled: false,
+        ..CubeState::new()
+    };
+    let mut game = Game::new_with_opening_roll(cube, 2);
+    game.apply(BLACK, Action::OpeningRoll(2, 2)).unwrap();
+    assert_eq!(game.cube().value, 1);
+}
@@ -0,0 +1,106 @@
+extern crate bgrs_logic;
+
+mod common;
+
+use bgrs_logic::{
+    BoardState, Location, Move, MoveError, PlayError, PlayerColor,
+};
+use common::{point, position};
+
+const BLACK: PlayerColor = PlayerColor::Black;
+
+#[test]
+fn legal_plays() {
+    let board = BoardState::new_starting_state(BLACK);
+    let play = [Move(point(8), point(5)), Move(point(6), point(5))];
+    assert_eq!(board.is_legal_play((3, 1), &play), Ok(()));
+    // the dice can be used in either order
+    let play = [Move(point(6), point(5)), Move(point(8), point(5))];
+    assert_eq!(board.is_legal_play((3, 1), &play), Ok(()));
+
+    let play = [Move(point(13), point(9)); 4];
+    assert_eq!(board.is_legal_play((4, 4), &play), Ok(()));
+}
+
+#[test]
+fn play_errors() {
+    let board = BoardState::new_starting_state(BLACK);
+
+    let play = [
+        Move(point(8), point(5)),
+        Move(point(6), point(5)),
+        Move(point(6), point(5)),
+    ];
+    assert_eq!(
+        board.is_legal_play((3, 1), &play),
+        Err(PlayError::TooManyMoves)
+    );
+    assert_eq!(
+        board.is_legal_play((2, 2), &[Move(point(6), point(4)); 5]),
+        Err(PlayError::TooManyMoves)
+    );
+
+    // moves are checked before the dice
+    let play = [Move(point(8), point(5)), Move(point(13), point(12))];
+    assert_eq!(
+        board.is_legal_play((3, 1), &play),
+        Err(PlayError::IllegalMove(1, MoveError::BlockedDestination))
+    );
+
+    let play = [Move(point(24), point(20)), Move(point(6), point(5))];
+    assert_eq!(
+        board.is_legal_play((3, 1), &play),
+        Err(PlayError::DiceMismatch(0))
+    );
+    // both moves need the 1
+    let play = [Move(point(6), point(5)), Move(point(6), point(5))];
+    assert_eq!(
+        board.is_legal_play((3, 1), &play),
+        Err(PlayError::DiceMismatch(1))
+    );
+
+    assert_eq!(
+        board.is_legal_play((3, 1), &[]),
+        Err(PlayError::TooFewMoves {
+            required: 2,
+            doubles: false,
+        })
+    );
+}
+
+#[test]
+fn must_use_larger_die() {
+    // black's last checker can play either die from the 13 point,
+    // but white holds the 2 point, so it can't play both
+    let board = position(&[(point(13), 1)], &[(point(23), 2)]);
+
+    assert_eq!(
+        board.is_legal_play((6, 5), &[Move(point(13), point(7))]),
+        Ok(())
+    );
+    assert_eq!(
+        board.is_legal_play((6, 5), &[Move(point(13), point(8))]),
+        Err(PlayError::MustUseLargerDie)
+    );
+    assert_eq!(
+        PlayError::MustUseLargerDie.to_string(),
+        "must use the larger die"
+    );
+}
+
+#[test]
+fn no_legal_plays() {
+    // black is on the bar against a closed board
+    let closed = (1..=6).map(|n| (point(n), 2)).collect::<Vec<_>>();
+    let board = position(&[(Location::Bar, 1), (point(6), 2)], &closed);
+
+    assert_eq!(board.is_legal_play((6, 5), &[]), Ok(()));
+    assert_eq!(
+        board.is_legal_play((6, 5), &[Move(Location::Bar, point(19))]),
+        Err(PlayError::IllegalMove(0, MoveError::BlockedDestination))
+    );
+    assert_eq!(
+        board.is_legal_play((6, 5), &[Move(point(6), point(1))]),
+        Err(PlayError::IllegalMove(0, MoveError::MustEnterFromBar))
+    );
+}
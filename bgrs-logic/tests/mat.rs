@@ -0,0 +1,417 @@
+extern crate bgrs_logic;
+
+mod common;
+
+use bgrs_logic::{
+    Action, CubeState, DiceSource, Game, GameResult, Location, MatError,
+    MatErrorKind, Match, MatchRecord, Move, NotationError, Phase, PlayError,
+    PlayerColor, SeededDice, WinKind,
+};
+use common::point;
+
+#[test]
+fn doubles_and_hits() {
+    let black = PlayerColor::Black;
+    let white = PlayerColor::White;
+
+    let mut game = Game::new_with_opening_roll(CubeState::new(), 0);
+    let actions = vec![
+        (black, Action::OpeningRoll(3, 1)),
+        (
+            black,
+            Action::Move(vec![
+                Move(point(13), point(10)),
+                Move(point(6), point(5)),
+            ]),
+        ),
+        (white, Action::Double),
+        (black, Action::Take),
+        (white, Action::Roll((6, 4))),
+        (
+            white,
+            Action::Move(vec![
+                Move(point(24), point(20)),
+                Move(point(13), point(7)),
+            ]),
+        ),
+        (black, Action::Double),
+        (white, Action::Drop),
+    ];
+
+    for (player, action) in actions {
+        game.apply(player, action).unwrap();
+    }
+
+    let mut record = MatchRecord::new(3, "alice", "bob");
+    record.games.push(game);
+
+    assert_eq!(
+        record.to_mat(),
+        " 3 point match
+
+ Game 1
+ alice : 0                       bob : 0
+  1) 31: 13/10 6/5                Doubles => 2
+  2)  Takes                      64: 24/20* 13/7
+  3)  Doubles => 4                Drops
+      Wins 2 points
+
+"
+    );
+}
+
+#[test]
+fn white_starts() {
+    let mut game = Game::new_with_opening_roll(CubeState::new(), 0);
+    game.apply(PlayerColor::Black, Action::OpeningRoll(2, 5))
+        .unwrap();
+    game.apply(
+        PlayerColor::White,
+        Action::Move(vec![
+            Move(point(13), point(8)),
+            Move(point(13), point(11)),
+        ]),
+    )
+    .unwrap();
+
+    let mut record = MatchRecord::new(0, "Black", "White");
+    record.games.push(game);
+
+    // the game isn't over, so nobody wins it
+    assert_eq!(
+        record.to_mat(),
+        " 0 point match
+
+ Game 1
+ Black : 0                       White : 0
+  1)                             52: 13/8 13/11
+
+"
+    );
+}
+
+// play a match, choosing plays and doubling arbitrarily
+fn random_match(seed: u64) -> MatchRecord {
+    let mut dice = SeededDice::new(seed);
+    let mut match_ = Match::new(5);
+    let mut record = MatchRecord::new(5, "alice", "bob");
+
+    while !match_.is_over() {
+        let mut game = match_.start_game();
+
+        while game.result().is_none() {
+            let player = game.player_to_act().unwrap_or(PlayerColor::Black);
+            match game.phase() {
+                Phase::AwaitingRoll
+                    if dice.roll_die() == 6
+                        && game.cube().check_double(player, false).is_ok() =>
+                {
+                    game.apply(player, Action::Double).unwrap();
+                }
+                Phase::OpeningRoll | Phase::AwaitingRoll => {
+                    game.roll(player, &mut dice).unwrap();
+                }
+                Phase::AwaitingMove(_) => {
+                    let plays = game.legal_plays();
+                    let move_seq = if plays.is_empty() {
+                        vec![]
+                    } else {
+                        plays[dice.roll_die() % plays.len()].clone()
+                    };
+                    game.apply(player, Action::Move(move_seq)).unwrap();
+                }
+                Phase::CubeDecision => {
+                    let action = if dice.roll_die() > 2 {
+                        Action::Take
+                    } else {
+                        Action::Drop
+                    };
+                    game.apply(player, action).unwrap();
+                }
+                Phase::GameOver(_) => unreachable!(),
+            }
+        }
+
+        match_.record_result(&game.result().unwrap(), game.cube());
+        record.games.push(game);
+    }
+
+    record
+}
+
+#[test]
+fn round_trip() {
+    for seed in 0..10 {
+        let record = random_match(seed);
+        let read = MatchRecord::from_mat(&record.to_mat()).unwrap();
+
+        assert_eq!(read.length, record.length);
+        assert_eq!(read.black_name, record.black_name);
+        assert_eq!(read.white_name, record.white_name);
+        assert_eq!(read.games.len(), record.games.len());
+        for (read_game, game) in read.games.iter().zip(&record.games) {
+            assert_eq!(read_game.result(), game.result());
+            assert_eq!(read_game.board(), game.board());
+            assert_eq!(read_game.cube(), game.cube());
+        }
+
+        assert_eq!(read.to_mat(), record.to_mat());
+    }
+}
+
+const OPENING: &str = " 1 point match
+
+ Game 1
+ alice : 0                       bob : 0
+";
+
+#[test]
+fn illegal_play() {
+    let mat = format!("{}  1) 62: 13/7 13/10\n", OPENING);
+    assert_eq!(
+        MatchRecord::from_mat(&mat),
+        Err(MatError {
+            line: 5,
+            kind: MatErrorKind::InvalidPlay(NotationError::IllegalPlay(
+                PlayError::DiceMismatch(1)
+            )),
+        })
+    );
+}
+
+#[test]
+fn ambiguous_play() {
+    // white's checker from 24 can hit one blot or two on the way to 14
+    let mat =
+        format!("{}  1) 62: 13/7 13/11              64: 24/14\n", OPENING);
+    assert_eq!(
+        MatchRecord::from_mat(&mat),
+        Err(MatError {
+            line: 5,
+            kind: MatErrorKind::InvalidPlay(NotationError::AmbiguousPlay),
+        })
+    );
+
+    let mat =
+        format!("{}  1) 62: 13/7 13/11              64: 24/14*\n", OPENING);
+    let record = MatchRecord::from_mat(&mat).unwrap();
+    assert_eq!(
+        record.games[0]
+            .board()
+            .checkers_at(PlayerColor::Black, Location::Bar),
+        1
+    );
+}
+
+#[test]
+fn invalid_lines() {
+    let error = |mat: &str| MatchRecord::from_mat(mat).unwrap_err();
+
+    assert_eq!(
+        error("not a match\n"),
+        MatError {
+            line: 1,
+            kind: MatErrorKind::InvalidHeader,
+        }
+    );
+    assert_eq!(
+        error(&format!("{}  1) 62 13/7 13/11\n", OPENING)),
+        MatError {
+            line: 5,
+            kind: MatErrorKind::InvalidLine,
+        }
+    );
+    assert_eq!(
+        error(&format!("{}  1) 72: 13/7 13/11\n", OPENING)),
+        MatError {
+            line: 5,
+            kind: MatErrorKind::InvalidDice,
+        }
+    );
+}
+
+#[test]
+fn cube_values() {
+    let error = |cube: &str| {
+        let mat =
+            format!("{}  1) 31: 8/5 6/5                 {}\n", OPENING, cube);
+        MatchRecord::from_mat(&mat).unwrap_err()
+    };
+
+    // the cube is at 1, so doubling turns it to 2
+    assert_eq!(
+        error("Doubles => 4"),
+        MatError {
+            line: 5,
+            kind: MatErrorKind::WrongCubeValue,
+        }
+    );
+    assert_eq!(
+        error("Doubles => two"),
+        MatError {
+            line: 5,
+            kind: MatErrorKind::InvalidLine,
+        }
+    );
+    assert_eq!(
+        error("Doubles to 2"),
+        MatError {
+            line: 5,
+            kind: MatErrorKind::InvalidLine,
+        }
+    );
+    assert_eq!(
+        MatErrorKind::WrongCubeValue.to_string(),
+        "cube value doesn't match the game"
+    );
+}
+
+// a match as gnubg exports it, with its header comments. the last game ends
+// with "and the match".
+const GNUBG_MATCH: &str = "; [Site \"GNU Backgammon\"]
+; [Player 1 \"alice\"]
+; [Player 2 \"gnubg\"]
+; [EventDate \"2024.03.09\"]
+; [EventTime \"20.14\"]
+; [Variation \"Backgammon\"]
+; [Unrated \"Off\"]
+; [Crawford \"On\"]
+; [CubeLimit \"1024\"]
+
+ 3 point match
+
+ Game 1
+ alice : 0                       gnubg : 0
+  1)                             31: 8/5 6/5
+  2) 64: 24/18 18/14              Doubles => 2
+  3)  Drops
+                                  Wins 1 point
+
+ Game 2
+ alice : 0                       gnubg : 1
+  1) 42: 8/4 6/4                 63: 24/18 13/10
+  2)  Doubles => 2                Takes
+  3) 55: 13/8 13/8 6/1* 6/1       Doubles => 4
+  4)  Takes                      66:
+  5)  Doubles => 8                Drops
+      Wins 4 points and the match
+
+";
+
+#[test]
+fn gnubg_match() {
+    let record = MatchRecord::from_mat(GNUBG_MATCH).unwrap();
+
+    assert_eq!(record.black_name, "alice");
+    assert_eq!(record.white_name, "gnubg");
+    assert_eq!(record.games.len(), 2);
+    assert_eq!(record.games[1].points(), Some(4));
+
+    // everything but the comments is written back the same way
+    let (_, body) = GNUBG_MATCH.split_once("\n\n").unwrap();
+    assert_eq!(record.to_mat(), body);
+}
+
+// games that end when the loser resigns, with only the points won
+const RESIGNED_MATCH: &str = " 3 point match
+
+ Game 1
+ alice : 0                       gnubg : 0
+  1)                             31: 8/5 6/5
+  2) 64: 24/18 18/14
+                                  Wins 2 points
+
+ Game 2
+ alice : 0                       gnubg : 2
+  1) 42: 8/4 6/4                 63: 24/18 13/10
+      Wins 1 point
+
+ Game 3
+ alice : 1                       gnubg : 2
+  1) 52: 13/8 13/11               Doubles => 2
+  2)  Takes
+      Wins 2 points and the match
+
+";
+
+#[test]
+fn resignations() {
+    let record = MatchRecord::from_mat(RESIGNED_MATCH).unwrap();
+
+    let results: Vec<_> = record
+        .games
+        .iter()
+        .map(|game| game.result().unwrap())
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            GameResult::new(PlayerColor::White, WinKind::Gammon),
+            GameResult::new(PlayerColor::Black, WinKind::Single),
+            GameResult::new(PlayerColor::Black, WinKind::Single),
+        ]
+    );
+
+    // white's gammon made the second game the crawford game, and the cube
+    // is back after it
+    assert!(!record.games[1].cube().enabled);
+    assert_eq!(record.games[2].cube().value, 2);
+
+    assert_eq!(record.to_mat(), RESIGNED_MATCH);
+
+    // black already won the match
+    let mat = format!(
+        "{} Game 4\n alice : 3                       gnubg : 2\n",
+        RESIGNED_MATCH
+    );
+    assert_eq!(
+        MatchRecord::from_mat(&mat),
+        Err(MatError {
+            line: 20,
+            kind: MatErrorKind::MatchOver,
+        })
+    );
+}
+
+#[test]
+fn wrong_scores_and_results() {
+    let error = |from: &str, to: &str| {
+        let mat = RESIGNED_MATCH.replacen(from, to, 1);
+        MatchRecord::from_mat(&mat).unwrap_err()
+    };
+
+    // the score after the first game
+    assert_eq!(
+        error(
+            "alice : 0                       gnubg : 2",
+            "alice : 0 gnubg : 1"
+        ),
+        MatError {
+            line: 10,
+            kind: MatErrorKind::WrongScore,
+        }
+    );
+
+    // a resignation can't be worth 3 points with the cube at 2
+    assert_eq!(
+        error("Wins 2 points and the match", "Wins 3 points"),
+        MatError {
+            line: 18,
+            kind: MatErrorKind::WrongResult,
+        }
+    );
+
+    // black dropped, so white won the game
+    let mat = format!(
+        "{}  1) 31: 8/5 6/5                 Doubles => 2\n  2)  Drops\n      \
+         Wins 1 point\n",
+        OPENING
+    );
+    assert_eq!(
+        MatchRecord::from_mat(&mat),
+        Err(MatError {
+            line: 7,
+            kind: MatErrorKind::WrongResult,
+        })
+    );
+}
@@ -0,0 +1,98 @@
+extern crate bgrs_logic;
+
+use bgrs_logic::{
+    Action, BoardState, CubeState, Game, Match, MatchId, MatchIdError,
+    MatchIdGameState, Phase, PlayerColor,
+};
+
+// example from the gnubg manual: a 9 point match where white leads 2-4 and
+// owns a 2 cube, and black has rolled 52
+const EXAMPLE_MATCH_ID: &str = "QYkqASAAIAAA";
+
+fn example() -> MatchId {
+    MatchId {
+        cube_value: 2,
+        cube_owner: Some(PlayerColor::White),
+        player_on_roll: PlayerColor::Black,
+        crawford: false,
+        game_state: MatchIdGameState::Playing,
+        turn: PlayerColor::Black,
+        double_offered: false,
+        resignation: None,
+        dice: Some((5, 2)),
+        match_length: 9,
+        black_score: 4,
+        white_score: 2,
+    }
+}
+
+#[test]
+fn decode_example() {
+    assert_eq!(MatchId::decode(EXAMPLE_MATCH_ID), Ok(example()));
+}
+
+#[test]
+fn encode_example() {
+    assert_eq!(example().encode(), EXAMPLE_MATCH_ID);
+}
+
+#[test]
+fn game_round_trip() {
+    let mut match_ = Match::new(9);
+    match_.black_score = 4;
+    match_.white_score = 2;
+
+    let mut cube = match_.new_cube();
+    cube.value = 2;
+    cube.owner = Some(PlayerColor::White);
+
+    let mut game = Game::new(PlayerColor::Black, cube);
+    game.apply(PlayerColor::Black, Action::Roll((5, 2)))
+        .unwrap();
+
+    let id = MatchId::from_game(&game, Some(&match_));
+    assert_eq!(id, example());
+
+    let board = game.board().clone();
+    let decoded = id.to_game(board).unwrap();
+    assert_eq!(decoded.phase(), Phase::AwaitingMove((5, 2)));
+    assert_eq!(decoded.cube(), game.cube());
+    assert_eq!(decoded.board(), game.board());
+    assert_eq!(id.to_match(), Some(match_));
+}
+
+#[test]
+fn cube_decision() {
+    let mut game = Game::new(PlayerColor::White, CubeState::new());
+    game.apply(PlayerColor::White, Action::Double).unwrap();
+
+    let id =
+        MatchId::decode(&MatchId::from_game(&game, None).encode()).unwrap();
+    assert!(id.double_offered);
+    assert_eq!(id.player_on_roll, PlayerColor::White);
+    assert_eq!(id.turn, PlayerColor::Black);
+    assert_eq!(id.to_match(), None);
+
+    let decoded = id
+        .to_game(BoardState::new_starting_state(PlayerColor::White))
+        .unwrap();
+    assert_eq!(decoded.phase(), Phase::CubeDecision);
+    assert_eq!(decoded.cube(), game.cube());
+}
+
+#[test]
+fn invalid_ids() {
+    assert_eq!(
+        MatchId::decode("QYkqASAAIAA"),
+        Err(MatchIdError::InvalidLength)
+    );
+    assert_eq!(
+        MatchId::decode("QYkqASAAIA.A"),
+        Err(MatchIdError::InvalidCharacter('.'))
+    );
+    // cube owner 2
+    assert_eq!(
+        MatchId::decode("IYkqASAAIAAA"),
+        Err(MatchIdError::InvalidCubeOwner)
+    );
+}
@@ -0,0 +1,102 @@
+extern crate bgrs_logic;
+
+use bgrs_logic::{CubeState, GameResult, Match, PlayerColor, WinKind};
+
+const BLACK: PlayerColor = PlayerColor::Black;
+const WHITE: PlayerColor = PlayerColor::White;
+
+// record a game won by winner with a cube at value
+fn win(match_: &mut Match, winner: PlayerColor, kind: WinKind, value: usize) {
+    match_.start_game();
+    let cube = CubeState {
+        value,
+        ..match_.new_cube()
+    };
+    match_.record_result(&GameResult::new(winner, kind), &cube);
+}
+
+#[test]
+fn score() {
+    let mut match_ = Match::new(7);
+    assert_eq!(match_.away(BLACK), 7);
+
+    let mut cube = CubeState::new();
+    cube.value = 2;
+    let points =
+        match_.record_result(&GameResult::new(BLACK, WinKind::Gammon), &cube);
+    assert_eq!(points, 4);
+    assert_eq!(match_.score(BLACK), 4);
+    assert_eq!(match_.away(BLACK), 3);
+    assert_eq!(match_.score(WHITE), 0);
+    assert_eq!(match_.get_winner(), None);
+
+    // winning more than needed still just wins the match
+    let points = match_
+        .record_result(&GameResult::new(BLACK, WinKind::Backgammon), &cube);
+    assert_eq!(points, 6);
+    assert_eq!(match_.away(BLACK), 0);
+    assert_eq!(match_.get_winner(), Some(BLACK));
+    assert!(match_.is_over());
+}
+
+#[test]
+fn crawford() {
+    let mut match_ = Match::new(5);
+    win(&mut match_, BLACK, WinKind::Single, 2);
+    assert!(!match_.crawford);
+    assert!(match_.new_cube().enabled);
+
+    // black gets to 1 away, so the next game is the crawford game
+    win(&mut match_, BLACK, WinKind::Gammon, 1);
+    assert_eq!(match_.away(BLACK), 1);
+    assert!(match_.crawford);
+    assert!(!match_.post_crawford);
+    assert!(!match_.new_cube().enabled);
+
+    let game = match_.start_game();
+    assert!(!game.cube().enabled);
+    assert_eq!(match_.game_count, 3);
+
+    // the crawford game only lasts one game, whoever wins it
+    match_.record_result(
+        &GameResult::new(WHITE, WinKind::Single),
+        &match_.new_cube(),
+    );
+    assert!(!match_.crawford);
+    assert!(match_.post_crawford);
+    assert!(match_.new_cube().enabled);
+
+    // white getting to 1 away after the crawford game doesn't start another
+    win(&mut match_, WHITE, WinKind::Backgammon, 1);
+    assert_eq!(match_.away(WHITE), 1);
+    assert!(!match_.crawford);
+    assert!(match_.new_cube().enabled);
+
+    win(&mut match_, WHITE, WinKind::Single, 1);
+    assert_eq!(match_.get_winner(), Some(WHITE));
+}
+
+#[test]
+fn no_crawford_game() {
+    // winning the match outright from further away skips the crawford game
+    let mut match_ = Match::new(3);
+    win(&mut match_, BLACK, WinKind::Single, 4);
+    assert!(match_.is_over());
+    assert!(!match_.crawford);
+
+    // a 1 point match starts out 1 away, but there's no game before it to
+    // make it the crawford game
+    let match_ = Match::new(1);
+    assert!(!match_.crawford);
+    assert!(match_.new_cube().enabled);
+}
+
+#[test]
+fn match_cube() {
+    let match_ = Match::new(3);
+    let cube = match_.new_cube();
+    assert_eq!(cube.value, 1);
+    assert!(cube.is_centered());
+    // beavers are only for money play
+    assert!(!cube.beavers_allowed);
+}
@@ -0,0 +1,102 @@
+extern crate bgrs_logic;
+
+mod common;
+
+use bgrs_logic::{BoardState, Location, Move, MoveError, PlayerColor};
+use common::{point, position};
+
+const BLACK: PlayerColor = PlayerColor::Black;
+const WHITE: PlayerColor = PlayerColor::White;
+
+#[test]
+fn legal_moves() {
+    let mut board = BoardState::new_starting_state(BLACK);
+    assert_eq!(board.try_apply_move(Move(point(13), point(7))), Ok(()));
+    assert_eq!(board.checkers_at(BLACK, point(13)), 4);
+    assert_eq!(board.checkers_at(BLACK, point(7)), 1);
+
+    // hitting a blot sends it to the bar
+    let mut board = position(&[(point(8), 1)], &[(point(20), 1)]);
+    assert_eq!(board.try_apply_move(Move(point(8), point(5))), Ok(()));
+    assert_eq!(board.checkers_at(WHITE, Location::Bar), 1);
+    assert_eq!(board.checkers_at(WHITE, point(20)), 0);
+
+    // entering, and bearing off
+    let mut board = position(&[(Location::Bar, 1)], &[(point(1), 1)]);
+    assert_eq!(board.try_apply_move(Move(Location::Bar, point(22))), Ok(()));
+    let mut board = position(&[(point(3), 2)], &[(point(24), 1)]);
+    assert_eq!(board.try_apply_move(Move(point(3), Location::Off)), Ok(()));
+    assert_eq!(board.borne_off(BLACK), 14);
+}
+
+#[test]
+fn move_errors() {
+    let check = |board: &BoardState, moves: &[(Move, MoveError)]| {
+        for &(move_, err) in moves {
+            let mut copy = board.clone();
+            assert_eq!(copy.try_apply_move(move_), Err(err), "{:?}", move_);
+            // nothing changes when a move fails
+            assert_eq!(&copy, board);
+        }
+    };
+
+    let bar = Location::Bar;
+    let off = Location::Off;
+    check(
+        &BoardState::new_starting_state(BLACK),
+        &[
+            (Move(point(25), point(20)), MoveError::InvalidLocation),
+            (Move(point(6), point(0)), MoveError::InvalidLocation),
+            (Move(point(7), point(3)), MoveError::NoCheckerAtSource),
+            (Move(off, point(3)), MoveError::NoCheckerAtSource),
+            (Move(bar, point(20)), MoveError::NoCheckerAtSource),
+            // white's checkers don't count as black's
+            (Move(point(1), point(3)), MoveError::NoCheckerAtSource),
+            (Move(point(6), point(8)), MoveError::WrongDirection),
+            (Move(point(6), point(6)), MoveError::WrongDirection),
+            (Move(point(6), bar), MoveError::WrongDirection),
+            (Move(point(13), point(12)), MoveError::BlockedDestination),
+            (Move(point(6), off), MoveError::IllegalBearOff),
+        ],
+    );
+
+    // white holds black's 19 point
+    check(
+        &position(&[(bar, 1), (point(6), 2)], &[(point(6), 2), (point(1), 1)]),
+        &[
+            (Move(point(6), point(2)), MoveError::MustEnterFromBar),
+            (Move(bar, point(19)), MoveError::BlockedDestination),
+            // checkers on the bar can't bear off either, but they must
+            // enter first
+            (Move(point(6), off), MoveError::MustEnterFromBar),
+        ],
+    );
+}
+
+#[test]
+fn move_seqs() {
+    let board = BoardState::new_starting_state(BLACK);
+
+    // a sequence is all or nothing
+    let mut copy = board.clone();
+    let moves = [Move(point(13), point(7)), Move(point(7), point(1))];
+    assert_eq!(
+        copy.try_apply_move_seq(moves.iter()),
+        Err(MoveError::BlockedDestination)
+    );
+    assert_eq!(copy, board);
+
+    let moves = [Move(point(13), point(7)), Move(point(7), point(4))];
+    assert_eq!(copy.try_apply_move_seq(moves.iter()), Ok(()));
+    assert_eq!(copy, board.with_move_seq(moves.iter()));
+    assert_eq!(copy.checkers_at(BLACK, point(4)), 1);
+}
+
+#[test]
+fn display() {
+    assert_eq!(MoveError::InvalidLocation.to_string(), "no such point");
+    assert_eq!(
+        MoveError::MustEnterFromBar.to_string(),
+        "must enter checkers from the bar first"
+    );
+}
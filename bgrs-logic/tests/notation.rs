@@ -0,0 +1,180 @@
+extern crate bgrs_logic;
+
+mod common;
+
+use bgrs_logic::{
+    BoardState, Location, Move, MoveError, NotationError, PlayError,
+    PlayerColor,
+};
+use common::point;
+
+fn start() -> BoardState {
+    BoardState::new_starting_state(PlayerColor::Black)
+}
+
+#[test]
+fn opening_moves() {
+    let board = start();
+
+    let moves = board.parse_play((3, 1), "8/5 6/5").unwrap();
+    assert_eq!(
+        moves,
+        vec![Move(point(8), point(5)), Move(point(6), point(5))]
+    );
+    assert_eq!(board.format_play(&moves).unwrap(), "8/5 6/5");
+
+    // one checker moving with both dice
+    let moves = board.parse_play((6, 5), "24/13").unwrap();
+    assert_eq!(moves.len(), 2);
+    assert_eq!(board.format_play(&moves).unwrap(), "24/13");
+
+    let moves = board.parse_play((6, 6), "24/18(2) 13/7(2)").unwrap();
+    assert_eq!(moves.len(), 4);
+    assert_eq!(board.format_play(&moves).unwrap(), "24/18(2) 13/7(2)");
+}
+
+#[test]
+fn format_sorts_and_groups() {
+    let board = start();
+    let moves = vec![
+        Move(point(6), point(5)),
+        Move(point(13), point(10)),
+        Move(point(6), point(5)),
+        Move(point(13), point(10)),
+    ];
+
+    assert_eq!(board.format_play(&moves).unwrap(), "13/10(2) 6/5(2)");
+    assert_eq!(board.format_play(&[]).unwrap(), "");
+}
+
+#[test]
+fn hits_and_bar() {
+    let mut board = start();
+    board.apply_move_seq(
+        [Move(point(13), point(7)), Move(point(13), point(11))].iter(),
+    );
+    board.end_turn();
+
+    // white hits black's blots on the way, and then without hitting
+    let double_hit = board.parse_play((6, 4), "24/18*/14*").unwrap();
+    assert_eq!(board.format_play(&double_hit).unwrap(), "24/18*/14*");
+
+    // the number of hits decides which route the checker took
+    assert_eq!(
+        board.parse_play((6, 4), "24/14"),
+        Err(NotationError::AmbiguousPlay)
+    );
+    let single_hit = board.parse_play((6, 4), "24/14*").unwrap();
+    assert_eq!(board.format_play(&single_hit).unwrap(), "24/14*");
+    assert_ne!(
+        board.with_move_seq(double_hit.iter()),
+        board.with_move_seq(single_hit.iter())
+    );
+
+    board.apply_move_seq(double_hit.iter());
+    board.end_turn();
+
+    let moves = board.parse_play((3, 1), "bar/22 bar/24").unwrap();
+    assert_eq!(board.format_play(&moves).unwrap(), "bar/24 bar/22");
+    // some programs write the bar as 25
+    assert_eq!(board.parse_play((3, 1), "25/22 25/24"), Ok(moves));
+}
+
+#[test]
+fn bearing_off() {
+    // all checkers on the 1 point
+    let board =
+        BoardState::from_position_id("/38AAAD/fwAAAA", PlayerColor::Black)
+            .unwrap();
+
+    let moves = board.parse_play((1, 1), "1/off(4)").unwrap();
+    assert_eq!(moves, vec![Move(point(1), Location::Off); 4]);
+    assert_eq!(board.format_play(&moves).unwrap(), "1/off(4)");
+    assert_eq!(board.parse_play((1, 1), "1/0(4)"), Ok(moves));
+}
+
+#[test]
+fn errors() {
+    let board = start();
+
+    assert_eq!(
+        board.parse_play((6, 5), "24/18 13-8"),
+        Err(NotationError::InvalidSyntax(1))
+    );
+    assert_eq!(
+        board.parse_play((6, 5), "24/18(0)"),
+        Err(NotationError::InvalidSyntax(0))
+    );
+    // more moves than any play can have
+    assert_eq!(
+        board.parse_play((6, 6), "13/7(5)"),
+        Err(NotationError::InvalidSyntax(0))
+    );
+    assert_eq!(
+        board.parse_play((3, 3), "6/3 8/5(300000000)"),
+        Err(NotationError::InvalidSyntax(1))
+    );
+    assert_eq!(
+        board.parse_play((6, 5), "24/18"),
+        Err(NotationError::IllegalPlay(PlayError::TooFewMoves {
+            required: 2,
+            doubles: false,
+        }))
+    );
+    assert_eq!(
+        PlayError::TooFewMoves {
+            required: 2,
+            doubles: false,
+        }
+        .to_string(),
+        "must use both dice"
+    );
+    assert_eq!(
+        board.parse_play((6, 6), "24/18(2)"),
+        Err(NotationError::IllegalPlay(PlayError::TooFewMoves {
+            required: 4,
+            doubles: true,
+        }))
+    );
+    assert_eq!(
+        PlayError::TooFewMoves {
+            required: 4,
+            doubles: true,
+        }
+        .to_string(),
+        "must play 4 moves"
+    );
+    // a double that can only be played twice
+    assert_eq!(
+        PlayError::TooFewMoves {
+            required: 2,
+            doubles: true,
+        }
+        .to_string(),
+        "must play 2 moves"
+    );
+    assert_eq!(
+        board.parse_play((6, 5), "24/18 13/9"),
+        Err(NotationError::IllegalPlay(PlayError::DiceMismatch(1)))
+    );
+}
+
+#[test]
+fn format_errors() {
+    let board = start();
+
+    // white holds black's 12 point
+    assert_eq!(
+        board.format_play(&[Move(point(13), point(12))]),
+        Err(MoveError::BlockedDestination)
+    );
+    // moves are made in order, so the second one has no checker to move
+    let moves = [Move(point(24), point(23)), Move(point(24), point(23))];
+    assert_eq!(board.format_play(&moves), Ok("24/23(2)".to_string()));
+    let moves = [Move(point(24), point(23)); 3];
+    assert_eq!(board.format_play(&moves), Err(MoveError::NoCheckerAtSource));
+    assert_eq!(
+        board.format_play(&[Move(point(26), point(20))]),
+        Err(MoveError::InvalidLocation)
+    );
+}
@@ -0,0 +1,113 @@
+extern crate bgrs_logic;
+
+mod common;
+
+use bgrs_logic::{BearoffTable, BoardState, PlayerColor, RaceAdvice};
+use common::{point, position};
+
+const BLACK: PlayerColor = PlayerColor::Black;
+const WHITE: PlayerColor = PlayerColor::White;
+
+#[test]
+fn starting_position() {
+    let board = BoardState::new_starting_state(BLACK);
+
+    assert_eq!(board.pip_count(BLACK), 167);
+    assert_eq!(board.pip_count(WHITE), 167);
+    assert_eq!(board.pip_difference(BLACK), 0);
+    assert!(!board.is_race());
+
+    // the 4 and 5 points are empty
+    assert_eq!(board.keith_count(BLACK), 169);
+    // only the 6 point is held in the home board
+    assert_eq!(board.thorp_count(BLACK), 167 + 30 - 1);
+}
+
+#[test]
+fn race_formulas() {
+    let black = (2..=6).map(|n| (point(n), 3)).collect::<Vec<_>>();
+    let white = (3..=7).map(|n| (point(n), 3)).collect::<Vec<_>>();
+    let mut board = position(&black, &white);
+
+    assert!(board.is_race());
+    assert_eq!(board.pip_count(BLACK), 60);
+    assert_eq!(board.pip_count(WHITE), 75);
+    assert_eq!(board.pip_difference(BLACK), 15);
+    assert_eq!(board.pip_difference(WHITE), -15);
+
+    // 2 extra checkers on the 2 point
+    assert_eq!(board.keith_count(BLACK), 62);
+    assert_eq!(board.keith_count(WHITE), 75);
+    // the 7 point isn't in white's home board
+    assert_eq!(board.thorp_count(BLACK), 60 + 30 - 5);
+    assert_eq!(board.thorp_count(WHITE), 75 + 30 - 4);
+
+    // black is far enough ahead that white should pass
+    let pass = RaceAdvice {
+        double: true,
+        redouble: true,
+        take: false,
+    };
+    assert_eq!(board.keith_advice(), pass);
+    assert_eq!(board.thorp_advice(), pass);
+    assert_eq!(board.rule_8_9_12_advice(), pass);
+    assert_eq!(pass.to_string(), "double, pass");
+
+    board.cur_player = WHITE;
+    let no_double = RaceAdvice {
+        double: false,
+        redouble: false,
+        take: true,
+    };
+    assert_eq!(board.keith_advice(), no_double);
+    assert_eq!(board.thorp_advice(), no_double);
+    assert_eq!(board.rule_8_9_12_advice(), no_double);
+}
+
+#[test]
+fn close_race() {
+    // 8-9-12: black leads by 6 pips out of 60, i.e. 10%
+    let black = (2..=6).map(|n| (point(n), 3)).collect::<Vec<_>>();
+    let mut white = (3..=6).map(|n| (point(n), 3)).collect::<Vec<_>>();
+    white.extend_from_slice(&[(point(1), 1), (point(2), 1), (point(9), 1)]);
+    let board = position(&black, &white);
+
+    assert_eq!(board.pip_difference(BLACK), 6);
+    assert_eq!(
+        board.rule_8_9_12_advice(),
+        RaceAdvice {
+            double: true,
+            redouble: true,
+            take: true,
+        }
+    );
+}
+
+#[test]
+fn effective_pip_count() {
+    let mut table = BearoffTable::new();
+
+    // a single checker on the 1 point always comes off in one roll
+    let board = position(&[(point(1), 1)], &[(point(1), 1)]);
+    assert!(
+        (board.effective_pip_count(BLACK, &mut table) - 49.0 / 6.0).abs()
+            < 1e-9
+    );
+
+    // 2 checkers come off each roll, or 4 with doubles
+    let board = position(&[(point(1), 15)], &[(point(1), 1)]);
+    assert!((board.effective_pip_count(BLACK, &mut table) - 57.0).abs() < 1e-3);
+
+    // nothing left to bear off
+    let board = position(&[], &[(point(1), 1)]);
+    assert_eq!(board.effective_pip_count(BLACK, &mut table), 0.0);
+
+    // some pips are always wasted
+    let board = position(&[(point(6), 2), (point(5), 1)], &[(point(1), 1)]);
+    assert!(board.effective_pip_count(BLACK, &mut table) > 17.0);
+
+    // a table that's already been used gives the same answers
+    let board = position(&[(point(1), 1)], &[(point(1), 1)]);
+    let fresh = board.effective_pip_count(BLACK, &mut BearoffTable::new());
+    assert_eq!(board.effective_pip_count(BLACK, &mut table), fresh);
+}
@@ -0,0 +1,110 @@
+extern crate bgrs_logic;
+
+mod common;
+
+use bgrs_logic::{BoardState, Move, PlayerColor, PointState};
+use common::point;
+use std::collections::HashSet;
+
+const BLACK: PlayerColor = PlayerColor::Black;
+const WHITE: PlayerColor = PlayerColor::White;
+
+// check that the plays for a roll group every legal ordering exactly once,
+// by the position it leads to
+fn check_grouping(board: &BoardState, dice_roll: (usize, usize)) {
+    let plays = board.get_distinct_plays(dice_roll);
+    let move_seqs = board.get_move_seqs(dice_roll);
+
+    let mut seen = HashSet::new();
+    for play in &plays {
+        assert!(play.equivalents.contains(&play.moves));
+        assert!(seen.insert(play.result.clone()), "{:?}", play.moves);
+
+        for move_seq in &play.equivalents {
+            assert_eq!(board.with_move_seq(move_seq.iter()), play.result);
+            // the representative is the first of the orderings
+            assert!(play.moves <= *move_seq);
+        }
+    }
+
+    let grouped: usize = plays.iter().map(|play| play.equivalents.len()).sum();
+    assert_eq!(grouped, move_seqs.len());
+    for move_seq in &move_seqs {
+        assert!(plays.iter().any(|play| play.equivalents.contains(move_seq)));
+    }
+}
+
+#[test]
+fn orderings() {
+    let board = BoardState::new_starting_state(BLACK);
+    let plays = board.get_distinct_plays((3, 1));
+
+    // making the 5 point can be played in either order
+    let play = plays
+        .iter()
+        .find(|play| play.result.checkers_at(BLACK, point(5)) == 2)
+        .unwrap();
+    assert_eq!(
+        play.moves,
+        vec![Move(point(8), point(5)), Move(point(6), point(5))]
+    );
+    assert_eq!(play.equivalents.len(), 2);
+    assert!(play
+        .equivalents
+        .contains(&vec![Move(point(6), point(5)), Move(point(8), point(5))]));
+
+    // so can moving one checker with both dice, through either point
+    let play = plays
+        .iter()
+        .find(|play| play.result.checkers_at(BLACK, point(20)) == 1)
+        .unwrap();
+    assert_eq!(
+        play.moves,
+        vec![Move(point(24), point(23)), Move(point(23), point(20))]
+    );
+    assert_eq!(play.equivalents.len(), 2);
+}
+
+#[test]
+fn grouping() {
+    let board = BoardState::new_starting_state(BLACK);
+    for die_1 in 1..=6 {
+        for die_2 in die_1..=6 {
+            check_grouping(&board, (die_1, die_2));
+        }
+    }
+
+    // doubles have lots of orderings for each play
+    let plays = board.get_distinct_plays((1, 1));
+    let play = plays
+        .iter()
+        .find(|play| play.result.checkers_at(BLACK, point(5)) == 2)
+        .unwrap();
+    assert!(play.equivalents.len() > 2);
+
+    let mut board = BoardState::new_starting_state(WHITE);
+    board.apply_move(Move(point(24), point(18)));
+    board.apply_move(Move(point(13), point(11)));
+    check_grouping(&board, (6, 4));
+    check_grouping(&board, (5, 5));
+}
+
+#[test]
+fn no_plays() {
+    // black is on the bar against a closed board
+    let mut points = [PointState::new(0, BLACK); 26];
+    points[0] = PointState::new(1, BLACK);
+    points[25] = PointState::new(0, WHITE);
+    for point in points[1..=6].iter_mut() {
+        *point = PointState::new(2, WHITE);
+    }
+    let board = BoardState {
+        points,
+        cur_player: BLACK,
+        black_borne_off: 14,
+        white_borne_off: 3,
+    };
+
+    assert!(board.get_distinct_plays((6, 5)).is_empty());
+    assert!(board.get_distinct_plays((1, 1)).is_empty());
+}
@@ -0,0 +1,86 @@
+extern crate bgrs_logic;
+
+use bgrs_logic::{
+    BoardState, DiceSource, Location, PlayerColor, PositionIdError, SeededDice,
+};
+
+const STARTING_POSITION_ID: &str = "4HPwATDgc/ABMA";
+
+#[test]
+fn starting_position() {
+    for &player in [PlayerColor::Black, PlayerColor::White].iter() {
+        let board = BoardState::new_starting_state(player);
+        assert_eq!(board.to_position_id(), STARTING_POSITION_ID);
+        assert_eq!(
+            BoardState::from_position_id(STARTING_POSITION_ID, player),
+            Ok(board)
+        );
+    }
+}
+
+#[test]
+fn all_on_ace_point() {
+    // both players have all 15 checkers on their own 1 point
+    let board =
+        BoardState::from_position_id("/38AAAD/fwAAAA", PlayerColor::Black)
+            .unwrap();
+
+    for &player in [PlayerColor::Black, PlayerColor::White].iter() {
+        assert_eq!(board.checkers_at(player, Location::Point(1)), 15);
+    }
+
+    assert_eq!(board.to_position_id(), "/38AAAD/fwAAAA");
+}
+
+#[test]
+fn round_trip() {
+    let mut dice = SeededDice::new(14);
+    let mut plays = vec![];
+
+    for _ in 0..20 {
+        let mut board = BoardState::new_starting_state(PlayerColor::White);
+
+        while board.get_winner().is_none() {
+            let id = board.to_position_id();
+            assert_eq!(id.len(), 14);
+            assert_eq!(
+                BoardState::from_position_id(&id, board.cur_player),
+                Ok(board.clone()),
+                "position id {}",
+                id
+            );
+
+            board.generate_plays(dice.roll_dice(), &mut plays);
+            if !plays.is_empty() {
+                let index = dice.roll_die() * 7 % plays.len();
+                board.apply_move_seq(plays[index].iter());
+            }
+
+            board.end_turn();
+        }
+    }
+}
+
+#[test]
+fn invalid_ids() {
+    let black = PlayerColor::Black;
+
+    assert_eq!(
+        BoardState::from_position_id("4HPwATDgc/ABM", black),
+        Err(PositionIdError::InvalidLength)
+    );
+    assert_eq!(
+        BoardState::from_position_id("4HPwATDgc/AB!A", black),
+        Err(PositionIdError::InvalidCharacter('!'))
+    );
+    // all bits set
+    assert_eq!(
+        BoardState::from_position_id("//////////////", black),
+        Err(PositionIdError::TooManyCheckers)
+    );
+    // both players have all their checkers on the same point
+    assert_eq!(
+        BoardState::from_position_id("/38AAAAAAID/Pw", black),
+        Err(PositionIdError::SharedPoint)
+    );
+}
@@ -0,0 +1,121 @@
+extern crate bgrs_logic;
+
+mod common;
+
+use bgrs_logic::{
+    BoardState, HeuristicEvaluator, Location, MoveFilter, PlayerColor, Search,
+    SearchSettings,
+};
+use common::{point, position};
+
+const BLACK: PlayerColor = PlayerColor::Black;
+const WHITE: PlayerColor = PlayerColor::White;
+
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+}
+
+fn search(plies: usize, filters: Vec<MoveFilter>) -> Search {
+    Search::new(
+        Box::new(HeuristicEvaluator::default()),
+        SearchSettings { plies, filters },
+    )
+}
+
+#[test]
+fn one_ply() {
+    // one ply picks the play that the evaluator likes best
+    let search = search(1, vec![]);
+
+    // bearing off both checkers wins the game
+    let board = position(&[(point(6), 1), (point(5), 1)], &[(point(6), 15)]);
+    let play = search.best_play(&board, (6, 5)).unwrap();
+    assert_eq!(play.result.get_winner(), Some(BLACK));
+
+    // hitting and making the point, without leaving any blots
+    let board = position(
+        &[(point(24), 2), (point(6), 13)],
+        &[(point(4), 1), (point(3), 7), (point(2), 7)],
+    );
+    let play = search.best_play(&board, (3, 3)).unwrap();
+    assert_eq!(play.result.checkers_at(WHITE, Location::Bar), 1);
+    assert_eq!(play.result.checkers_at(BLACK, point(21)), 2);
+
+    // no legal plays from the bar
+    let board = position(
+        &[(Location::Bar, 1), (point(6), 14)],
+        &[(point(1), 2), (point(2), 13)],
+    );
+    assert_eq!(search.best_play(&board, (1, 2)), None);
+}
+
+#[test]
+fn deterministic() {
+    let board = BoardState::new_starting_state(WHITE);
+    let first = search(2, SearchSettings::default().filters);
+    let second = search(2, SearchSettings::default().filters);
+
+    let play = first.best_play(&board, (5, 2)).unwrap();
+    assert_eq!(first.best_play(&board, (5, 2)), Some(play.clone()));
+    assert_eq!(second.best_play(&board, (5, 2)), Some(play));
+}
+
+#[test]
+fn filters() {
+    let board = BoardState::new_starting_state(BLACK);
+
+    // keeping only the evaluator's favorite leaves nothing to search
+    let narrow = search(
+        2,
+        vec![MoveFilter {
+            accept: 1,
+            extra: 0,
+            threshold: 0.0,
+        }],
+    );
+    assert_eq!(
+        narrow.best_play(&board, (4, 3)),
+        search(1, vec![]).best_play(&board, (4, 3)),
+    );
+
+    // a filter that keeps everything is the same as none
+    let wide = search(
+        2,
+        vec![MoveFilter {
+            accept: 100,
+            extra: 0,
+            threshold: 0.0,
+        }],
+    );
+    assert_eq!(
+        wide.best_play(&board, (4, 3)),
+        search(2, vec![]).best_play(&board, (4, 3)),
+    );
+}
+
+#[test]
+fn evaluate_position() {
+    let search = search(2, SearchSettings::default().filters);
+
+    // 0 plies is just the evaluator
+    let board = BoardState::new_starting_state(BLACK);
+    assert_eq!(
+        search.evaluate_position(&board, 0),
+        search.evaluator.evaluate(&board),
+    );
+
+    // black bears off with any roll, before white has borne off anything
+    let board = position(&[(point(1), 2)], &[(point(6), 15)]);
+    let probs = search.evaluate_position(&board, 1);
+    assert_close(probs.win, 1.0);
+    assert_close(probs.win_gammon, 1.0);
+    assert_eq!(probs.win_backgammon, 0.0);
+
+    // black needs a 6, or doubles other than 1-1, and otherwise white
+    // bears off next turn
+    let board = position(&[(point(6), 1), (point(1), 1)], &[(point(1), 2)]);
+    let probs = search.evaluate_position(&board, 2);
+    assert_close(probs.win, 15.0 / 36.0);
+    assert_eq!(probs.win_gammon, 0.0);
+    assert_eq!(probs.lose_gammon, 0.0);
+}
@@ -0,0 +1,203 @@
+#![cfg(feature = "serde")]
+
+extern crate bgrs_logic;
+extern crate serde_json;
+
+use bgrs_logic::{
+    Action, BoardState, CompactBoard, CubeState, DiceSource, Game, Location,
+    Move, Phase, PlayBuf, PlayerColor, SeededDice, Setup,
+};
+use serde_json::{json, Value};
+
+fn round_trip<T>(value: &T) -> T
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+}
+
+#[test]
+fn game_round_trip() {
+    let mut dice = SeededDice::new(3);
+    let mut game = Game::new_with_opening_roll(CubeState::new(), 0);
+    while game.history().len() < 20 {
+        let player = game.player_to_act().unwrap_or(PlayerColor::Black);
+        match game.phase() {
+            Phase::AwaitingMove(_) => {
+                let play = game.legal_plays().into_iter().next();
+                game.apply(player, Action::Move(play.unwrap_or_default()))
+                    .unwrap();
+            }
+            _ => {
+                game.roll(player, &mut dice).unwrap();
+            }
+        }
+    }
+
+    assert_eq!(round_trip(&game), game);
+    assert_eq!(round_trip(game.board()), *game.board());
+
+    let compact = game.board().to_compact();
+    assert_eq!(round_trip(&compact), compact);
+
+    // dice pick up where they left off
+    let mut copy = round_trip(&dice);
+    assert_eq!(copy.roll_dice(), dice.roll_dice());
+}
+
+#[test]
+fn play_buf() {
+    let mut play = PlayBuf::new();
+    play.push(Move(Location::Point(13), Location::Point(8)));
+    play.push(Move(Location::Point(13), Location::Point(11)));
+
+    let json = serde_json::to_value(play).unwrap();
+    assert_eq!(
+        json,
+        json!([[{"Point": 13}, {"Point": 8}], [{"Point": 13}, {"Point": 11}]])
+    );
+    assert_eq!(round_trip(&play), play);
+
+    let too_long = Value::Array(vec![json!([{"Point": 6}, {"Point": 5}]); 5]);
+    assert!(serde_json::from_value::<PlayBuf>(too_long).is_err());
+}
+
+fn board_json() -> Value {
+    serde_json::to_value(BoardState::new_starting_state(PlayerColor::Black))
+        .unwrap()
+}
+
+#[test]
+fn invalid_boards() {
+    let check = |json: Value| {
+        assert!(serde_json::from_value::<BoardState>(json.clone()).is_err());
+        assert!(serde_json::from_value::<CompactBoard>(json).is_err());
+    };
+
+    assert!(serde_json::from_value::<BoardState>(board_json()).is_ok());
+
+    // a checker missing
+    let mut json = board_json();
+    json["points"][1]["checker_count"] = json!(1);
+    check(json);
+
+    // a checker too many, even if it's been borne off
+    let mut json = board_json();
+    json["white_borne_off"] = json!(1);
+    check(json);
+
+    // counts that add up to 15 once they overflow
+    let mut json = board_json();
+    json["points"][1]["checker_count"] = json!(usize::MAX);
+    json["points"][12]["checker_count"] = json!(8);
+    check(json);
+    let mut json = board_json();
+    json["black_borne_off"] = json!(usize::MAX - 14);
+    check(json);
+
+    // a bar with the other player's color
+    let mut json = board_json();
+    json["points"][0]["checker_color"] = json!("White");
+    check(json);
+}
+
+fn game_json() -> (Game, Value) {
+    let mut game = Game::new_with_opening_roll(CubeState::new(), 1);
+    let actions = vec![
+        (PlayerColor::Black, Action::OpeningRoll(2, 2)),
+        (PlayerColor::Black, Action::OpeningRoll(3, 1)),
+        (
+            PlayerColor::Black,
+            Action::Move(vec![
+                Move(Location::Point(8), Location::Point(5)),
+                Move(Location::Point(6), Location::Point(5)),
+            ]),
+        ),
+        (PlayerColor::White, Action::Double),
+        (PlayerColor::Black, Action::Take),
+        (PlayerColor::White, Action::Roll((6, 4))),
+    ];
+    for (player, action) in actions {
+        game.apply(player, action).unwrap();
+    }
+
+    let json = serde_json::to_value(&game).unwrap();
+    (game, json)
+}
+
+#[test]
+fn invalid_games() {
+    let check = |json: Value| {
+        assert!(serde_json::from_value::<Game>(json).is_err());
+    };
+
+    let (game, json) = game_json();
+    assert_eq!(game.cube().value, 4);
+    assert_eq!(serde_json::from_value::<Game>(json).unwrap(), game);
+
+    // a game that was set up from where this one is also round trips, and
+    // the setup has to make sense
+    let setup = Setup {
+        board: game.board().clone(),
+        cube: *game.cube(),
+        phase: game.phase(),
+    };
+    let mut from_setup = Game::from_setup(setup.clone());
+    let play = from_setup.legal_plays().remove(0);
+    from_setup
+        .apply(PlayerColor::White, Action::Move(play))
+        .unwrap();
+    assert_eq!(round_trip(&from_setup), from_setup);
+
+    let mut json = serde_json::to_value(&from_setup).unwrap();
+    json["setup"]["phase"] = json!({"AwaitingMove": [6, 7]});
+    check(json);
+    let mut json = serde_json::to_value(Game::from_setup(setup)).unwrap();
+    json["setup"]["phase"] = json!("CubeDecision");
+    json["phase"] = json!("CubeDecision");
+    check(json);
+
+    // a phase that doesn't follow from the history
+    let (_, mut json) = game_json();
+    json["phase"] = json!("AwaitingRoll");
+    check(json);
+
+    // a cube that was never doubled
+    let (_, mut json) = game_json();
+    json["cube"]["value"] = json!(8);
+    check(json);
+    let (_, mut json) = game_json();
+    json["cube"]["owner"] = json!("White");
+    check(json);
+
+    // history with an illegal play, or missing its last action
+    let (_, mut json) = game_json();
+    json["history"][2][1]["Move"][0][1] = json!({"Point": 4});
+    check(json);
+    let (_, mut json) = game_json();
+    json["history"].as_array_mut().unwrap().pop();
+    check(json);
+
+    // a board that doesn't match the plays
+    let (_, mut json) = game_json();
+    json["board"] = board_json();
+    check(json);
+}
+
+#[test]
+fn invalid_locations() {
+    assert_eq!(
+        serde_json::from_value::<Location>(json!({"Point": 24})).unwrap(),
+        Location::Point(24)
+    );
+    assert_eq!(
+        serde_json::from_value::<Location>(json!("Bar")).unwrap(),
+        Location::Bar
+    );
+
+    for n in &[0, 25, 99] {
+        let json = json!({ "Point": n });
+        assert!(serde_json::from_value::<Location>(json.clone()).is_err());
+        assert!(serde_json::from_value::<Move>(json!([json, "Off"])).is_err());
+    }
+}
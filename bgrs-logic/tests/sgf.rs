@@ -0,0 +1,432 @@
+extern crate bgrs_logic;
+
+mod common;
+
+use bgrs_logic::{
+    Action, CubeState, DiceSource, Game, GameError, GameResult, Location,
+    Match, MatchRecord, Move, Phase, PlayError, PlayerColor, SeededDice, Setup,
+    Sgf, SgfError, SgfErrorKind, WinKind,
+};
+use common::point;
+
+#[test]
+fn write_game() {
+    let black = PlayerColor::Black;
+    let white = PlayerColor::White;
+
+    let mut game = Game::new_with_opening_roll(CubeState::new(), 0);
+    let actions = vec![
+        (black, Action::OpeningRoll(3, 1)),
+        (
+            black,
+            Action::Move(vec![
+                Move(point(8), point(5)),
+                Move(point(6), point(5)),
+            ]),
+        ),
+        (white, Action::Double),
+        (black, Action::Take),
+        (white, Action::Roll((6, 4))),
+        (
+            white,
+            Action::Move(vec![
+                Move(point(24), point(18)),
+                Move(point(13), point(9)),
+            ]),
+        ),
+        (black, Action::Double),
+        (white, Action::Drop),
+    ];
+
+    for (player, action) in actions {
+        game.apply(player, action).unwrap();
+    }
+
+    let mut record = MatchRecord::new(3, "alice", "bob");
+    record.games.push(game);
+
+    assert_eq!(
+        Sgf::from_match(&record).to_sgf(),
+        "(;FF[4]GM[6]CA[UTF-8]AP[bgrs]MI[length:3][game:0][ws:0][bs:0]\
+         PB[alice]PW[bob]RU[Crawford]RE[B+2]
+;B[31hefe]
+;W[double]
+;B[take]
+;W[64aglp]
+;B[double]
+;W[drop])
+"
+    );
+}
+
+// a game as gnubg writes it, with analysis, comments and properties bgrs
+// doesn't know about
+const GNUBG_GAME: &str = "(;FF[4]GM[6]CA[UTF-8]AP[GNU Backgammon:1.06.002]
+MI[length:0][game:0][ws:0][bs:0][wtime:0][btime:0][wtimeouts:0][btimeouts:0]
+PW[gnubg]PB[me]RU[]
+;B[52mhmk]A[0][-0.010][4 ply]C[a comment with \\] and \\\\ in it]
+;W[63aglo]BM[1]
+;B[double]DA[ver:3][Eq:0.1]
+;W[take])
+";
+
+#[test]
+fn read_gnubg_game() {
+    let sgf = Sgf::parse(GNUBG_GAME).unwrap();
+    let nodes = sgf.trees[0].main_line();
+    assert_eq!(nodes.len(), 5);
+    assert_eq!(nodes[1].value("C"), Some("a comment with ] and \\ in it"));
+    assert_eq!(
+        nodes[1].get("A"),
+        Some(&["0".to_string(), "-0.010".to_string(), "4 ply".to_string()][..])
+    );
+
+    let record = sgf.to_match().unwrap();
+    assert_eq!(record.length, 0);
+    assert_eq!(record.black_name, "me");
+    assert_eq!(record.white_name, "gnubg");
+
+    let game = &record.games[0];
+    assert_eq!(game.phase(), Phase::AwaitingRoll);
+    assert_eq!(game.cube().value, 2);
+    assert_eq!(game.cube().owner, Some(PlayerColor::White));
+    assert_eq!(
+        game.history()[1],
+        (
+            PlayerColor::Black,
+            Action::Move(vec![
+                Move(point(13), point(8)),
+                Move(point(13), point(11)),
+            ])
+        )
+    );
+    assert_eq!(
+        game.history()[3],
+        (
+            PlayerColor::White,
+            Action::Move(vec![
+                Move(point(24), point(18)),
+                Move(point(13), point(10)),
+            ])
+        )
+    );
+
+    // nothing is lost when writing it back
+    let written = sgf.to_sgf();
+    assert_eq!(Sgf::parse(&written), Ok(sgf.clone()));
+    assert_eq!(written, Sgf::parse(&written).unwrap().to_sgf());
+}
+
+#[test]
+fn variations() {
+    let sgf = Sgf::parse("(;GM[6];B[31hefe](;W[double];B[drop])(;W[64aglp]))")
+        .unwrap();
+    assert_eq!(sgf.trees[0].variations.len(), 2);
+    assert_eq!(Sgf::parse(&sgf.to_sgf()), Ok(sgf.clone()));
+
+    // only the first variation is played
+    let record = sgf.to_match().unwrap();
+    let result = record.games[0].result().unwrap();
+    assert_eq!(result.winner, PlayerColor::White);
+    assert_eq!(result.kind, WinKind::Single);
+}
+
+#[test]
+fn setup_position() {
+    // a bear-off: black has 2 checkers on the 1 point and white has one on
+    // the bar and one on its 1 point. black is on roll with 21 and owns a 2
+    // cube.
+    let sgf = Sgf::parse(
+        "(;GM[6]MI[length:0]AE[a:y]AB[a][a]AW[x][y]PL[B]CV[2]CP[b]DI[21]
+;B[21azaz])",
+    )
+    .unwrap();
+    let record = sgf.to_match().unwrap();
+    let game = &record.games[0];
+
+    let setup = game.setup().unwrap();
+    let board = &setup.board;
+    assert_eq!(board.checkers_at(PlayerColor::Black, point(1)), 2);
+    assert_eq!(board.checkers_at(PlayerColor::White, point(1)), 1);
+    assert_eq!(board.checkers_at(PlayerColor::White, Location::Bar), 1);
+    assert_eq!(board.borne_off(PlayerColor::Black), 13);
+    assert_eq!(setup.cube.value, 2);
+    assert_eq!(setup.cube.owner, Some(PlayerColor::Black));
+    assert_eq!(setup.phase, Phase::AwaitingMove((2, 1)));
+
+    let result = game.result().unwrap();
+    assert_eq!(result.winner, PlayerColor::Black);
+    assert_eq!(result.kind, WinKind::Single);
+    assert_eq!(game.points(), Some(2));
+
+    // the setup is written back, and reads as the same game
+    let written = Sgf::from_match(&record);
+    assert_eq!(written.to_match(), Ok(record.clone()));
+    assert_eq!(
+        written.trees[0].nodes[0].get("AB"),
+        Some(&["a".to_string(), "a".to_string()][..])
+    );
+}
+
+#[test]
+fn setup_from_game() {
+    let board = bgrs_logic::BoardState::from_position_id(
+        "4HPwATDgc/ABMA",
+        PlayerColor::White,
+    )
+    .unwrap();
+    let game = Game::from_setup(Setup {
+        board,
+        cube: CubeState::new(),
+        phase: Phase::AwaitingRoll,
+    });
+
+    let mut record = MatchRecord::new(0, "Black", "White");
+    record.games.push(game);
+
+    let read = Sgf::parse(&Sgf::from_match(&record).to_sgf())
+        .unwrap()
+        .to_match()
+        .unwrap();
+    assert_eq!(read, record);
+}
+
+// play a match, choosing plays and doubling arbitrarily
+fn random_match(seed: u64) -> MatchRecord {
+    let mut dice = SeededDice::new(seed);
+    let mut match_ = Match::new(5);
+    let mut record = MatchRecord::new(5, "alice", "bob");
+
+    while !match_.is_over() {
+        let mut game = match_.start_game();
+
+        while game.result().is_none() {
+            let player = game.player_to_act().unwrap_or(PlayerColor::Black);
+            match game.phase() {
+                Phase::AwaitingRoll
+                    if dice.roll_die() == 6
+                        && game.cube().check_double(player, false).is_ok() =>
+                {
+                    game.apply(player, Action::Double).unwrap();
+                }
+                Phase::OpeningRoll | Phase::AwaitingRoll => {
+                    game.roll(player, &mut dice).unwrap();
+                }
+                Phase::AwaitingMove(_) => {
+                    let plays = game.legal_plays();
+                    let move_seq = if plays.is_empty() {
+                        vec![]
+                    } else {
+                        plays[dice.roll_die() % plays.len()].clone()
+                    };
+                    game.apply(player, Action::Move(move_seq)).unwrap();
+                }
+                Phase::CubeDecision => {
+                    let action = if dice.roll_die() > 2 {
+                        Action::Take
+                    } else {
+                        Action::Drop
+                    };
+                    game.apply(player, action).unwrap();
+                }
+                Phase::GameOver(_) => unreachable!(),
+            }
+        }
+
+        match_.record_result(&game.result().unwrap(), game.cube());
+        record.games.push(game);
+    }
+
+    record
+}
+
+#[test]
+fn round_trip() {
+    for seed in 0..10 {
+        let record = random_match(seed);
+        let text = Sgf::from_match(&record).to_sgf();
+        let read = Sgf::parse(&text).unwrap().to_match().unwrap();
+
+        assert_eq!(read.length, record.length);
+        assert_eq!(read.black_name, record.black_name);
+        assert_eq!(read.white_name, record.white_name);
+        assert_eq!(read.games.len(), record.games.len());
+        for (read_game, game) in read.games.iter().zip(&record.games) {
+            assert_eq!(read_game.result(), game.result());
+            assert_eq!(read_game.board(), game.board());
+            assert_eq!(read_game.cube(), game.cube());
+        }
+
+        assert_eq!(Sgf::from_match(&read).to_sgf(), text);
+    }
+}
+
+#[test]
+fn errors() {
+    let error = |text: &str| Sgf::parse(text).and_then(|sgf| sgf.to_match());
+
+    assert_eq!(
+        error("(;GM[6];B[31hefe)"),
+        Err(SgfError {
+            game: 1,
+            node: 2,
+            kind: SgfErrorKind::Syntax,
+        })
+    );
+    assert_eq!(
+        error("(;GM[6])\n(;GM[1])"),
+        Err(SgfError {
+            game: 2,
+            node: 1,
+            kind: SgfErrorKind::NotBackgammon,
+        })
+    );
+    assert_eq!(
+        error("(;GM[6];B[71hefe])"),
+        Err(SgfError {
+            game: 1,
+            node: 2,
+            kind: SgfErrorKind::InvalidValue("B"),
+        })
+    );
+    assert_eq!(
+        error("(;GM[6];B[31hefd])"),
+        Err(SgfError {
+            game: 1,
+            node: 2,
+            kind: SgfErrorKind::InvalidAction(GameError::IllegalPlay(
+                PlayError::DiceMismatch(1)
+            )),
+        })
+    );
+    assert_eq!(
+        error("(;GM[6];B[31hefe];W[take])"),
+        Err(SgfError {
+            game: 1,
+            node: 3,
+            kind: SgfErrorKind::InvalidAction(GameError::WrongPhase),
+        })
+    );
+    assert_eq!(
+        error("(;GM[6];B[31hefe];AB[a])"),
+        Err(SgfError {
+            game: 1,
+            node: 3,
+            kind: SgfErrorKind::SetupAfterStart,
+        })
+    );
+    assert_eq!(
+        error(
+            "(;GM[6]AE[a:y]AB[a][a][a][a][a][a][a][a][a][a][a][a][a][a][a][a])"
+        ),
+        Err(SgfError {
+            game: 1,
+            node: 1,
+            kind: SgfErrorKind::InvalidPosition,
+        })
+    );
+    // more than a u8 can count, on a point that already has checkers
+    let many = format!("(;GM[6]AW{})", "[f]".repeat(300));
+    assert_eq!(
+        error(&many),
+        Err(SgfError {
+            game: 1,
+            node: 1,
+            kind: SgfErrorKind::InvalidPosition,
+        })
+    );
+    // deeply nested variations are rejected instead of overflowing the
+    // stack
+    let nested = "(;GM[6]".repeat(200_000);
+    assert_eq!(
+        Sgf::parse(&nested).map_err(|err| err.kind),
+        Err(SgfErrorKind::TooDeep)
+    );
+    let nested = format!("{}{}", "(;GM[6]".repeat(1000), ")".repeat(1000));
+    assert!(Sgf::parse(&nested).is_ok());
+    assert_eq!(
+        error("(;GM[6]RE[B+1];B[31hefe];W[double];B[drop])"),
+        Err(SgfError {
+            game: 1,
+            node: 1,
+            kind: SgfErrorKind::WrongResult,
+        })
+    );
+}
+
+// a 3 point match where bob resigns a gammon in the first game and alice
+// resigns a single game with the cube at 2 in the second
+const RESIGNED_MATCH: &str =
+    "(;FF[4]GM[6]CA[UTF-8]AP[bgrs]MI[length:3][game:0][ws:0][bs:0]\
+PB[alice]PW[bob]RU[Crawford]RE[B+2R]
+;B[31hefe]
+;W[resign]
+;B[accept])
+(;FF[4]GM[6]CA[UTF-8]AP[bgrs]MI[length:3][game:1][ws:0][bs:2]\
+PB[alice]PW[bob]RU[Crawford:CrawfordGame]RE[W+1R]
+;B[31hefe])
+";
+
+#[test]
+fn resignations() {
+    let record = Sgf::parse(RESIGNED_MATCH).unwrap().to_match().unwrap();
+    assert_eq!(record.games.len(), 2);
+    assert_eq!(
+        record.games[0].result(),
+        Some(GameResult::new(PlayerColor::Black, WinKind::Gammon))
+    );
+    assert_eq!(record.games[0].points(), Some(2));
+    assert_eq!(
+        record.games[1].result(),
+        Some(GameResult::new(PlayerColor::White, WinKind::Single))
+    );
+    assert!(!record.games[1].cube().enabled);
+
+    // the conceded points decide the score in the next game's MI
+    let text = Sgf::from_match(&record).to_sgf();
+    assert!(text.contains("RE[B+2R]"));
+    assert!(text.contains("MI[length:3][game:1][ws:0][bs:2]"));
+    assert!(text.contains("RE[W+1R]"));
+    assert!(!text.contains("resign"));
+    let read = Sgf::parse(&text).unwrap().to_match().unwrap();
+    assert_eq!(Sgf::from_match(&read).to_sgf(), text);
+}
+
+#[test]
+fn resumed_match() {
+    // a file with just the Crawford game of a match, which alice leads 2-0
+    let record = Sgf::parse(
+        "(;GM[6]MI[length:3][game:1][ws:0][bs:2]RU[Crawford:CrawfordGame]\
+         RE[W+1R];B[31hefe])\n\
+         (;GM[6]MI[length:3][game:2][ws:1][bs:2]RE[B+1R];B[31hefe])",
+    )
+    .unwrap()
+    .to_match()
+    .unwrap();
+    assert!(!record.games[0].cube().enabled);
+    assert!(record.games[1].cube().enabled);
+
+    let error = |text: &str| Sgf::parse(text).and_then(|sgf| sgf.to_match());
+    assert_eq!(
+        error(
+            "(;GM[6]MI[length:3][game:0][ws:0][bs:0]RE[B+1R];B[31hefe])\n\
+             (;GM[6]MI[length:3][game:1][ws:0][bs:2];B[31hefe])"
+        ),
+        Err(SgfError {
+            game: 2,
+            node: 1,
+            kind: SgfErrorKind::WrongScore,
+        })
+    );
+    assert_eq!(
+        error(
+            "(;GM[6]MI[length:3][ws:0][bs:2]RE[B+1R];B[31hefe])\n\
+             (;GM[6];B[31hefe])"
+        ),
+        Err(SgfError {
+            game: 2,
+            node: 1,
+            kind: SgfErrorKind::MatchOver,
+        })
+    );
+}
@@ -0,0 +1,112 @@
+extern crate bgrs_logic;
+
+use bgrs_logic::{
+    Action, BoardState, Game, Location, Match, Move, Phase, PlayerColor, Xgid,
+    XgidError,
+};
+
+// starting position, money game with jacoby and beavers, black to play 52
+const OPENING_52: &str = "XGID=-b----E-C---eE---c-e----B-:0:0:1:52:0:0:3:0:10";
+
+#[test]
+fn starting_position() {
+    let xgid = Xgid::decode(OPENING_52).unwrap();
+
+    assert_eq!(
+        xgid.board,
+        BoardState::new_starting_state(PlayerColor::Black)
+    );
+    assert_eq!(xgid.dice, Some((5, 2)));
+    assert_eq!(xgid.cube_value, 1);
+    assert_eq!(xgid.cube_owner, None);
+    assert!(xgid.jacoby && xgid.beavers && !xgid.crawford);
+    assert_eq!(xgid.match_length, 0);
+    assert_eq!(xgid.max_cube, 1024);
+    assert_eq!(xgid.to_match(), None);
+    assert_eq!(xgid.to_game().phase(), Phase::AwaitingMove((5, 2)));
+
+    assert_eq!(xgid.encode(), OPENING_52);
+}
+
+#[test]
+fn prefix_is_optional() {
+    assert_eq!(
+        Xgid::decode(&OPENING_52["XGID=".len()..]),
+        Xgid::decode(OPENING_52)
+    );
+}
+
+#[test]
+fn match_with_double_offered() {
+    let mut match_ = Match::new(7);
+    match_.black_score = 3;
+    match_.white_score = 1;
+
+    let mut game = Game::new(PlayerColor::White, match_.new_cube());
+    game.apply(PlayerColor::White, Action::Roll((6, 4)))
+        .unwrap();
+    game.apply(
+        PlayerColor::White,
+        Action::Move(vec![
+            Move(Location::Point(24), Location::Point(18)),
+            Move(Location::Point(18), Location::Point(14)),
+        ]),
+    )
+    .unwrap();
+    game.apply(PlayerColor::Black, Action::Double).unwrap();
+
+    let encoded = Xgid::from_game(&game, Some(&match_)).encode();
+    assert_eq!(
+        encoded,
+        "XGID=-a----E-C--aeE---c-e----B-:0:0:-1:D:3:1:0:7:10"
+    );
+
+    let xgid = Xgid::decode(&encoded).unwrap();
+    assert_eq!(&xgid.board, game.board());
+    assert!(xgid.double_offered);
+    assert_eq!(xgid.turn(), PlayerColor::White);
+    assert_eq!(xgid.to_match(), Some(match_));
+
+    let decoded = xgid.to_game();
+    assert_eq!(decoded.phase(), Phase::CubeDecision);
+    assert_eq!(decoded.cube(), game.cube());
+}
+
+#[test]
+fn invalid_xgids() {
+    let invalid = |xgid: &str| Xgid::decode(xgid).unwrap_err();
+
+    assert_eq!(
+        invalid("XGID=-b----E-C---eE---c-e----B-:0:0:1:52:0:0:3:0"),
+        XgidError::WrongFieldCount(9)
+    );
+    assert_eq!(
+        invalid("XGID=-b----E-C---eE---c-e----B:0:0:1:52:0:0:3:0:10"),
+        XgidError::InvalidPosition
+    );
+    assert_eq!(
+        invalid("XGID=-b----E-C---eE---c-e----Bz:0:0:1:52:0:0:3:0:10"),
+        XgidError::InvalidPosition
+    );
+    // white checker on black's bar
+    assert_eq!(
+        invalid("XGID=-b----E-C---eE---c-e----Ba:0:0:1:52:0:0:3:0:10"),
+        XgidError::InvalidPosition
+    );
+    assert_eq!(
+        invalid("XGID=-b----E-C---eE---c-e----O-:0:0:1:52:0:0:3:0:10"),
+        XgidError::TooManyCheckers
+    );
+    assert_eq!(
+        invalid("XGID=-b----E-C---eE---c-e----B-:0:0:2:52:0:0:3:0:10"),
+        XgidError::InvalidField("turn")
+    );
+    assert_eq!(
+        invalid("XGID=-b----E-C---eE---c-e----B-:0:0:1:72:0:0:3:0:10"),
+        XgidError::InvalidField("dice")
+    );
+    assert_eq!(
+        invalid("XGID=-b----E-C---eE---c-e----B-:0:0:1:52:x:0:3:0:10"),
+        XgidError::InvalidField("score")
+    );
+}
@@ -1,62 +1,140 @@
 extern crate bgrs_logic;
-extern crate dedup_iter;
 extern crate rand;
 
-use bgrs_logic::{BoardState, Move, PlayerColor};
-use dedup_iter::DedupAdapter;
+use bgrs_logic::{
+    opening_roll_winner, Action, BoardState, CubeState, DiceRoll, DiceSource,
+    DieRoll, Game, HeuristicEvaluator, ManualDice, MatchRecord, Move, Phase,
+    PlayerColor, Search, SearchSettings, SeededDice,
+};
 use rand::Rng;
-use std::fmt::Display;
+use std::env;
+use std::fs;
 use std::io::{self, Write};
+use std::process;
+
+// how white chooses its plays
+enum Bot {
+    // picks any legal play
+    Random,
+    // looks ahead for the best play
+    Search,
+    // the search looking just one play ahead, which is what the greedy bot
+    // used to be. --plies doesn't change it.
+    Greedy,
+}
 
-type DieRoll = usize;
+struct Options {
+    seed: Option<u64>,
+    bot: Bot,
+    // how far ahead the search bot looks
+    plies: usize,
+    manual_dice: bool,
+    // where to save a record of the game
+    save_path: Option<String>,
+}
 
-fn roll_die() -> DieRoll {
-    let mut rng = rand::thread_rng();
-    rng.gen_range(1, 7)
+fn usage() -> ! {
+    eprintln!(
+        "usage: bgrs-tui [--seed SEED] [--manual-dice] [--save FILE] \
+         [--bot search|greedy|random] [--plies N]"
+    );
+    process::exit(1);
 }
 
-type DiceRoll = (DieRoll, DieRoll);
+fn parse_args() -> Options {
+    let mut options = Options {
+        seed: None,
+        bot: Bot::Search,
+        plies: SearchSettings::default().plies,
+        manual_dice: false,
+        save_path: None,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let seed = args.next().and_then(|s| s.parse().ok());
+                options.seed = Some(seed.unwrap_or_else(|| usage()));
+            }
+            "--manual-dice" => options.manual_dice = true,
+            "--save" => {
+                options.save_path = Some(args.next().unwrap_or_else(|| usage()))
+            }
+            "--bot" => {
+                options.bot = match args.next().as_deref() {
+                    Some("search") => Bot::Search,
+                    Some("greedy") => Bot::Greedy,
+                    Some("random") => Bot::Random,
+                    _ => usage(),
+                }
+            }
+            "--plies" => {
+                let plies = args.next().and_then(|s| s.parse().ok());
+                match plies {
+                    Some(plies) if plies > 0 => options.plies = plies,
+                    _ => usage(),
+                }
+            }
+            _ => usage(),
+        }
+    }
 
-fn roll_dice() -> DiceRoll {
-    (roll_die(), roll_die())
+    options
 }
 
-fn uniq_map<I, T, F, R>(iterator: I, f: F) -> Vec<R>
-where
-    I: Iterator<Item = T>,
-    F: FnMut(T) -> R,
-    R: Ord,
-{
-    let mut ret: Vec<R> = iterator.map(f).collect();
-    ret.sort();
-    ret.dedup();
-    ret
+fn read_die() -> DieRoll {
+    loop {
+        let die = get_num_input("die? (1-6): ").expect("input error");
+        if (1..=6).contains(&die) {
+            return die as DieRoll;
+        }
+    }
 }
 
-fn get_num_input(prompt: &str) -> io::Result<isize> {
-    let stdin = io::stdin();
-    let mut stdout = io::stdout();
+fn get_input(prompt: &str) -> io::Result<String> {
     let mut line = String::new();
 
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    if io::stdin().read_line(&mut line)? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    Ok(line.trim().to_string())
+}
+
+fn get_num_input(prompt: &str) -> io::Result<isize> {
     loop {
-        print!("{}", prompt);
-        stdout.flush()?;
-        stdin.read_line(&mut line)?;
-        if let Ok(num) = line.trim().parse() {
+        if let Ok(num) = get_input(prompt)?.parse() {
             return Ok(num);
         }
     }
 }
 
-fn fmt_array<T>(array: &[T]) -> String
-where
-    T: Display,
-{
-    array
-        .iter()
-        .map(|x| format!("{}", x))
-        .collect::<Vec<String>>()
-        .join(", ")
+// the board, with pip counts above it. in a race, also show what the race
+// formulas say about doubling for the player on roll.
+fn print_board(board: &BoardState) {
+    let black = board.pip_count(PlayerColor::Black);
+    let white = board.pip_count(PlayerColor::White);
+    let lead = match board.pip_difference(PlayerColor::Black) {
+        0 => "even".to_string(),
+        diff if diff > 0 => format!("b leads by {}", diff),
+        diff => format!("w leads by {}", -diff),
+    };
+    println!("pips: b {}, w {} ({})", black, white, lead);
+
+    if board.is_race() {
+        println!(
+            "race: keith {}; thorp {}; 8-9-12 {}",
+            board.keith_advice(),
+            board.thorp_advice(),
+            board.rule_8_9_12_advice(),
+        );
+    }
+
+    println!();
+    board.print();
 }
 
 // returns player's valid move, or None if there isn't any
@@ -64,85 +142,31 @@ fn get_human_player_move_seq(
     board: &BoardState,
     dice: DiceRoll,
 ) -> io::Result<Option<Vec<Move>>> {
-    let valid_move_seqs = board.get_move_seqs(dice);
-    if valid_move_seqs.is_empty() {
-        board.print();
+    print_board(board);
+
+    let plays = board.get_distinct_plays(dice);
+    if plays.is_empty() {
         println!("No available moves!");
         return Ok(None);
     }
 
-    // all valid_move_seqs are the same length, as player must make the maximum
-    // number of moves available. so we can just check the length of the first
-    // sequence.
-    let expected_length = valid_move_seqs[0].len();
-
-    let mut cur_board = board.clone();
-    let mut ret = Vec::new();
-    while ret.len() < expected_length {
-        cur_board.print();
-
-        let valid_next_moves: Vec<Move> = valid_move_seqs
-            .iter()
-            .filter(|&s| s.starts_with(&ret))
-            .map(|s| s[ret.len()])
-            // iterator generation basically makes it sorted, so this removes
-            // all duplicates
-            .dedup()
-            .collect();
-
-        let valid_start_points = uniq_map(valid_next_moves.iter(), |m| m.0);
-
-        let start_point = get_num_input(&format!(
-            "start point? ({}{}): ",
-            fmt_array(&valid_start_points),
-            if !ret.is_empty() { "; -1 to undo" } else { "" },
-        ))?;
-
-        if start_point < 0 {
-            ret.pop();
-            cur_board = board.with_move_seq(ret.iter());
-            continue;
-        }
-
-        let start_point = start_point as usize;
-        if !valid_start_points.contains(&start_point) {
-            // ignore
-            continue;
-        }
-
-        let valid_next_moves: Vec<Move> = valid_next_moves
-            .into_iter()
-            .filter(|m| m.0 == start_point)
-            .collect();
-
-        let valid_distances =
-            uniq_map(valid_next_moves.iter(), |m| m.die_roll());
-
-        let distance = get_num_input(&format!(
-            "distance? ({}; -1 to undo): ",
-            fmt_array(&valid_distances),
-        ))?;
-
-        if distance < 0 {
-            // undo start point; don't modify ret
+    loop {
+        let input = get_input("move? (e.g. 13/7 8/7, ? for legal moves): ")?;
+        if input == "?" {
+            for play in &plays {
+                println!(
+                    "  {}",
+                    board.format_play(&play.moves).expect("illegal play")
+                );
+            }
             continue;
         }
 
-        let distance = distance as usize;
-
-        // look for original Move object so we don't have to calculate end point
-        // ourselves + validate distance
-        let move_ = valid_next_moves.iter().find(|m| m.die_roll() == distance);
-        if let Some(&move_) = move_ {
-            ret.push(move_);
-            cur_board = cur_board.with_move(move_);
-        } else {
-            // not one of the valid moves. ignore.
-            continue;
+        match board.parse_play(dice, &input) {
+            Ok(move_seq) => return Ok(Some(move_seq)),
+            Err(err) => println!("{}", err),
         }
     }
-
-    Ok(Some(ret))
 }
 
 fn get_random_move_seq(
@@ -151,48 +175,158 @@ fn get_random_move_seq(
 ) -> Option<Vec<Move>> {
     let mut rng = rand::thread_rng();
 
-    board.print();
+    print_board(board);
 
-    let valid_move_seqs = board.get_move_seqs(dice);
-    if valid_move_seqs.is_empty() {
+    // choose between resulting positions, not move orderings, so that plays
+    // with many orderings aren't more likely
+    let plays = board.get_distinct_plays(dice);
+    if plays.is_empty() {
         println!("No available moves!");
     } else {
         println!("choosing...");
     }
 
-    rng.choose(&valid_move_seqs).cloned()
+    rng.choose(&plays).map(|play| play.moves.clone())
+}
+
+fn get_search_move_seq(
+    board: &BoardState,
+    dice: DiceRoll,
+    search: &Search,
+) -> Option<Vec<Move>> {
+    print_board(board);
+
+    let play = search.best_play(board, dice);
+    if play.is_none() {
+        println!("No available moves!");
+    } else {
+        println!("thinking...");
+    }
+
+    play.map(|play| play.moves)
+}
+
+fn save_game(game: &Game, path: &str) {
+    let mut record = MatchRecord::new(0, "Black", "White");
+    record.games.push(game.clone());
+
+    match fs::write(path, record.to_mat()) {
+        Ok(()) => println!("Game saved to {}", path),
+        Err(err) => eprintln!("Can't save game to {}: {}", path, err),
+    }
 }
 
 fn main() {
-    let mut board = BoardState::new_starting_state(PlayerColor::Black);
+    let options = parse_args();
+
+    let mut dice: Box<dyn DiceSource> = if options.manual_dice {
+        Box::new(ManualDice::new(read_die))
+    } else {
+        let seed = options
+            .seed
+            .unwrap_or_else(|| rand::thread_rng().gen::<u64>());
+        println!("Dice seed: {}", seed);
+        Box::new(SeededDice::new(seed))
+    };
+
+    let search = Search::new(
+        Box::new(HeuristicEvaluator::default()),
+        SearchSettings {
+            plies: match options.bot {
+                Bot::Greedy => 1,
+                _ => options.plies,
+            },
+            ..SearchSettings::default()
+        },
+    );
+
+    let mut game = Game::new_with_opening_roll(CubeState::new(), 0);
+    game.set_dice_seed(dice.seed());
+
+    let save_path = options.save_path.unwrap_or_else(|| match dice.seed() {
+        Some(seed) => format!("bgrs-{}.mat", seed),
+        None => "bgrs.mat".to_string(),
+    });
 
     loop {
-        if let Some(winner) = board.get_winner() {
-            println!();
-            println!("*** {} won! ***", winner);
-            println!();
-            break;
-        }
+        let player = game.board().cur_player;
+
+        match game.phase() {
+            Phase::GameOver(result) => {
+                let points = game.points().unwrap();
+                println!();
+                println!(
+                    "*** {}! ({} point{}) ***",
+                    result,
+                    points,
+                    if points == 1 { "" } else { "s" },
+                );
+                println!();
+
+                save_game(&game, &save_path);
+                break;
+            }
 
-        println!("*** {}'s turn! ***", board.cur_player);
+            Phase::OpeningRoll => {
+                let (black_die, white_die) = dice.roll_dice();
+                println!(
+                    "Opening roll: {} rolls {}, {} rolls {}",
+                    PlayerColor::Black,
+                    black_die,
+                    PlayerColor::White,
+                    white_die,
+                );
+
+                match opening_roll_winner(black_die, white_die) {
+                    Some(winner) => {
+                        println!("*** {} starts! ***", winner);
+                        println!("Dice: {:?}", (black_die, white_die));
+                    }
+                    None => println!("Tie, rolling again..."),
+                }
+
+                // both players roll, so it doesn't matter who we say it is
+                game.apply(player, Action::OpeningRoll(black_die, white_die))
+                    .expect("opening roll failed");
+            }
 
-        let dice = roll_dice();
-        println!("Dice: {:?}", dice);
+            Phase::AwaitingRoll => {
+                println!("*** {}'s turn! ***", player);
 
-        let move_seq = match board.cur_player {
-            PlayerColor::Black => {
-                get_human_player_move_seq(&board, dice).expect("input error")
+                let roll = dice.roll_dice();
+                println!("Dice: {:?}", roll);
+                game.apply(player, Action::Roll(roll))
+                    .expect("rolling failed");
             }
-            PlayerColor::White => get_random_move_seq(&board, dice),
-        };
 
-        if let Some(move_seq) = move_seq {
-            println!("Making move: {:?}", move_seq);
-            board = board.with_move_seq(move_seq.iter());
-        }
-
-        board.end_turn();
+            Phase::AwaitingMove(roll) => {
+                let board = game.board();
+                let move_seq = match player {
+                    PlayerColor::Black => get_human_player_move_seq(board, roll)
+                        .expect("input error"),
+                    PlayerColor::White => match options.bot {
+                        Bot::Random => get_random_move_seq(board, roll),
+                        Bot::Search | Bot::Greedy => {
+                            get_search_move_seq(board, roll, &search)
+                        }
+                    },
+                };
+
+                if let Some(ref move_seq) = move_seq {
+                    println!(
+                        "{} plays {}",
+                        player,
+                        board.format_play(move_seq).expect("illegal play")
+                    );
+                }
+
+                game.apply(player, Action::Move(move_seq.unwrap_or_default()))
+                    .expect("illegal move");
+
+                println!();
+            }
 
-        println!();
+            Phase::CubeDecision => unreachable!("tui doesn't use the cube"),
+        }
     }
 }
@@ -1,10 +1,12 @@
 extern crate bgrs_logic;
 
-use bgrs_logic::{BoardState, PlayerColor, PointState};
+use bgrs_logic::{BoardState, Location, PlayerColor, PointState};
 use yew::prelude::*;
 
 use super::bar::Bar;
+use super::checker_group::CheckerGroupVAlign;
 use super::point::{Point, PointDirection};
+use super::tray::Tray;
 
 #[derive(Clone, PartialEq)]
 pub struct BoardProps {
@@ -68,8 +70,35 @@ impl Renderable<Board> for Board {
                     x=self.point_x_index_to_x(7),
                     width=self.point_width(),
                     height=height,
-                    black_count=board.points[0].checker_count,
-                    white_count=board.points[25].checker_count,
+                    black_count=board.checkers_at(
+                        PlayerColor::Black,
+                        Location::Bar,
+                    ),
+                    white_count=board.checkers_at(
+                        PlayerColor::White,
+                        Location::Bar,
+                    ),
+                />
+
+                // black bears off from the top half, white from the bottom
+                <Tray:
+                    x=self.point_x_index_to_x(14),
+                    y=self.point_y_index_to_y(0),
+                    width=self.point_width(),
+                    height=self.point_height(),
+                    valign=CheckerGroupVAlign::Top,
+                    count=board.black_borne_off,
+                    color=PlayerColor::Black,
+                />
+
+                <Tray:
+                    x=self.point_x_index_to_x(14),
+                    y=self.point_y_index_to_y(1),
+                    width=self.point_width(),
+                    height=self.point_height(),
+                    valign=CheckerGroupVAlign::Bottom,
+                    count=board.white_borne_off,
+                    color=PlayerColor::White,
                 />
             </svg>
         }
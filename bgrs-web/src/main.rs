@@ -12,21 +12,22 @@ mod checker;
 mod checker_group;
 mod point;
 mod svg;
+mod tray;
 
 use self::board::Board;
-use bgrs_logic::{BoardState, PlayerColor};
+use bgrs_logic::{CubeState, Game, PlayerColor};
 
-struct Game {
-    state: BoardState,
+struct Model {
+    game: Game,
 }
 
-impl Component for Game {
+impl Component for Model {
     type Message = ();
     type Properties = ();
 
     fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
-        Game {
-            state: BoardState::new_starting_state(PlayerColor::Black),
+        Model {
+            game: Game::new(PlayerColor::Black, CubeState::new()),
         }
     }
 
@@ -35,11 +36,11 @@ impl Component for Game {
     }
 }
 
-impl Renderable<Game> for Game {
+impl Renderable<Model> for Model {
     fn view(&self) -> Html<Self> {
         html! {
             <>
-                <Board: board=self.state.clone(), />
+                <Board: board=self.game.board().clone(), />
             </>
         }
     }
@@ -47,6 +48,6 @@ impl Renderable<Game> for Game {
 
 fn main() {
     yew::initialize();
-    App::<Game>::new().mount_to_body();
+    App::<Model>::new().mount_to_body();
     yew::run_loop();
 }
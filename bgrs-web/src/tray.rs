@@ -0,0 +1,99 @@
+use bgrs_logic::PlayerColor;
+use yew::prelude::*;
+
+use super::checker_group::{CheckerGroup, CheckerGroupVAlign};
+use super::svg::translate;
+
+#[derive(Clone, PartialEq)]
+pub struct TrayProps {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub valign: CheckerGroupVAlign,
+    pub count: usize,
+    pub color: PlayerColor,
+}
+
+impl Default for TrayProps {
+    fn default() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+            valign: CheckerGroupVAlign::Top,
+            count: 0,
+            color: PlayerColor::Black,
+        }
+    }
+}
+
+// holds the checkers that a player has already borne off
+pub struct Tray {
+    props: TrayProps,
+}
+
+impl Component for Tray {
+    type Message = ();
+    type Properties = TrayProps;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Tray { props }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> bool {
+        self.props = props;
+        true
+    }
+
+    fn update(&mut self, _msg: ()) -> ShouldRender {
+        true
+    }
+}
+
+impl Renderable<Tray> for Tray {
+    fn view(&self) -> Html<Self> {
+        let TrayProps {
+            x,
+            y,
+            width,
+            height,
+            valign,
+            count,
+            color,
+        } = self.props;
+
+        // checkers are drawn from the board's edge towards the middle
+        let (group_y, label_y) = match valign {
+            CheckerGroupVAlign::Bottom => (height, 0),
+            _ => (0, height),
+        };
+
+        html! {
+            <g transform=translate(x, y),>
+                <rect
+                    class="tray-bg",
+                    width=width,
+                    height=height,
+                />
+
+                <CheckerGroup:
+                    x=width / 2,
+                    y=group_y,
+                    valign=valign,
+                    count=count,
+                    color=color,
+                />
+
+                <text
+                    class="tray-count",
+                    x=width / 2,
+                    y=label_y,
+                >
+                    { count }
+                </text>
+            </g>
+        }
+    }
+}